@@ -377,12 +377,44 @@ pub fn parse_strict_json<T: Field>(
     ))
 }
 
+/// Parse raw inputs given as decimal or `0x`-prefixed hexadecimal strings, rejecting values outside of the field
+pub fn parse_public_inputs<T: Field>(inputs: &[String]) -> Result<Vec<T>, Error> {
+    inputs
+        .iter()
+        .map(|s| {
+            match s.starts_with("0x") {
+                true => T::from_hex_str(s),
+                false => T::from_dec_str(s),
+            }
+            .map_err(|_| Error::Conversion(format!("Could not parse `{}` to field type", s)))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use zokrates_ast::typed::types::{ConcreteStructMember, ConcreteStructType, ConcreteType};
     use zokrates_field::Bn128Field;
 
+    #[test]
+    fn public_inputs() {
+        assert_eq!(
+            parse_public_inputs::<Bn128Field>(&["42".into(), "0x2a".into()]),
+            Ok(vec![Bn128Field::from(42), Bn128Field::from(42)])
+        );
+
+        let modulus = (Bn128Field::max_value().to_biguint() + 1u32).to_string();
+        assert_eq!(
+            parse_public_inputs::<Bn128Field>(&["1".into(), modulus.clone()]),
+            Err(Error::Conversion(format!(
+                "Could not parse `{}` to field type",
+                modulus
+            )))
+        );
+        assert!(parse_public_inputs::<Bn128Field>(&["0xzz".into()]).is_err());
+    }
+
     #[test]
     fn numbers() {
         let s = "[1, 2]";
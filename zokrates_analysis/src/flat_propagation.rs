@@ -7,7 +7,7 @@
 use std::collections::HashMap;
 use zokrates_ast::flat::folder::*;
 use zokrates_ast::flat::*;
-use zokrates_field::Field;
+use zokrates_field::{Field, Pow};
 
 #[derive(Default)]
 struct Propagator<T> {
@@ -59,6 +59,10 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<T> {
                     (e1, e2) => FlatExpression::Mult(box e1, box e2),
                 }
             }
+            FlatExpression::Pow(box e, exponent) => match self.fold_expression(e) {
+                FlatExpression::Number(n) => FlatExpression::Number(n.pow(exponent as usize)),
+                e => FlatExpression::Pow(box e, exponent),
+            },
         }
     }
 }
@@ -13,7 +13,7 @@ use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::ops::{BitAnd, BitOr, BitXor, Shl, Shr, Sub};
-use zokrates_ast::common::FlatEmbed;
+use zokrates_ast::common::{FlatEmbed, SourceMetadata};
 use zokrates_ast::typed::result_folder::*;
 use zokrates_ast::typed::types::Type;
 use zokrates_ast::typed::*;
@@ -27,6 +27,17 @@ pub enum Error {
     AssertionFailed(RuntimeError),
     InvalidValue(String),
     OutOfBounds(u128, u128),
+    DivisionByZero(String),
+}
+
+impl Error {
+    /// The location in the source of the statement which caused this error, if known
+    pub fn source_metadata(&self) -> Option<&SourceMetadata> {
+        match self {
+            Error::AssertionFailed(RuntimeError::SourceAssertion(metadata)) => Some(metadata),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -40,6 +51,11 @@ impl fmt::Display for Error {
                 "Out of bounds index ({} >= {}) found during static analysis",
                 index, size
             ),
+            Error::DivisionByZero(e) => write!(
+                f,
+                "Division of `{}` by zero found during static analysis",
+                e
+            ),
         }
     }
 }
@@ -49,20 +65,58 @@ pub struct Propagator<'ast, 'a, T: Field> {
     // constants keeps track of constant expressions
     // we currently do not support partially constant expressions: `field [x, 1][1]` is not considered constant, `field [0, 1][1]` is
     constants: &'a mut Constants<'ast, T>,
+    // if enabled, the statements which were folded away along with the reason why
+    audit_log: Option<Vec<(String, String)>>,
+    // the identifiers defined as the bit decomposition of an expression, along with the embed used
+    decompositions: HashMap<Identifier<'ast>, (FlatEmbed, TypedExpression<'ast, T>)>,
 }
 
 impl<'ast, 'a, T: Field> Propagator<'ast, 'a, T> {
     pub fn with_constants(constants: &'a mut Constants<'ast, T>) -> Self {
-        Propagator { constants }
+        Propagator {
+            constants,
+            audit_log: None,
+            decompositions: HashMap::new(),
+        }
     }
 
     pub fn propagate(p: TypedProgram<'ast, T>) -> Result<TypedProgram<'ast, T>, Error> {
         let mut constants = Constants::new();
 
-        Propagator {
-            constants: &mut constants,
+        Propagator::with_constants(&mut constants).fold_program(p)
+    }
+
+    /// Same as `propagate`, also returning the number of constants learned
+    pub fn propagate_with_stats(
+        p: TypedProgram<'ast, T>,
+    ) -> Result<(TypedProgram<'ast, T>, usize), Error> {
+        let mut constants = Constants::new();
+
+        let p = Propagator::with_constants(&mut constants).fold_program(p)?;
+
+        Ok((p, constants.len()))
+    }
+
+    /// The number of constants learned so far
+    pub fn constants_len(&self) -> usize {
+        self.constants.len()
+    }
+
+    /// Record the statements removed by this propagator, see `audit_log`
+    pub fn with_audit_log(mut self) -> Self {
+        self.audit_log = Some(vec![]);
+        self
+    }
+
+    /// The statements removed so far as `(statement, reason)` pairs, if recording was enabled with `with_audit_log`
+    pub fn audit_log(&self) -> Option<&Vec<(String, String)>> {
+        self.audit_log.as_ref()
+    }
+
+    fn record_removal(&mut self, statement: Option<String>, reason: &str) {
+        if let (Some(log), Some(statement)) = (self.audit_log.as_mut(), statement) {
+            log.push((statement, reason.to_string()));
         }
-        .fold_program(p)
     }
 
     // get a mutable reference to the constant corresponding to a given assignee if any, otherwise
@@ -141,6 +195,224 @@ impl<'ast, 'a, T: Field> Propagator<'ast, 'a, T> {
     }
 }
 
+/// Rebuild the chain of `And` (if `conjunction` is set) or `Or` made of `e1` and `e2` without its identity
+/// literals and duplicate operands, or return the absorbing literal if it appears in the chain
+fn normalize_chain<'ast, T: Field>(
+    e1: BooleanExpression<'ast, T>,
+    e2: BooleanExpression<'ast, T>,
+    conjunction: bool,
+) -> BooleanExpression<'ast, T> {
+    fn flatten<'ast, T: Field>(
+        e: BooleanExpression<'ast, T>,
+        conjunction: bool,
+        operands: &mut Vec<BooleanExpression<'ast, T>>,
+    ) {
+        match e {
+            BooleanExpression::And(box e1, box e2) if conjunction => {
+                flatten(e1, conjunction, operands);
+                flatten(e2, conjunction, operands);
+            }
+            BooleanExpression::Or(box e1, box e2) if !conjunction => {
+                flatten(e1, conjunction, operands);
+                flatten(e2, conjunction, operands);
+            }
+            e => {
+                if !operands.contains(&e) {
+                    operands.push(e)
+                }
+            }
+        }
+    }
+
+    let mut operands = vec![];
+    flatten(e1, conjunction, &mut operands);
+    flatten(e2, conjunction, &mut operands);
+
+    if operands.contains(&BooleanExpression::Value(!conjunction)) {
+        return BooleanExpression::Value(!conjunction);
+    }
+
+    operands
+        .into_iter()
+        .filter(|e| *e != BooleanExpression::Value(conjunction))
+        .reduce(|acc, e| match conjunction {
+            true => BooleanExpression::And(box acc, box e),
+            false => BooleanExpression::Or(box acc, box e),
+        })
+        .unwrap_or(BooleanExpression::Value(conjunction))
+}
+
+/// Return the strict version of the comparison `e1` if `e2` makes it strict, for example `a < b` for `a <= b && a != b`
+/// or `a <= b && a < b`
+fn strict_comparison<'ast, T: Field>(
+    e1: &BooleanExpression<'ast, T>,
+    e2: &BooleanExpression<'ast, T>,
+) -> Option<BooleanExpression<'ast, T>> {
+    fn is_not_eq<E: PartialEq>(e: &EqExpression<E>, a: &E, b: &E) -> bool {
+        (*e.left == *a && *e.right == *b) || (*e.left == *b && *e.right == *a)
+    }
+
+    use BooleanExpression::*;
+
+    match (e1, e2) {
+        (FieldLe(a, b), FieldLt(c, d)) | (FieldGe(a, b), FieldGt(c, d)) if a == c && b == d => {
+            Some(e2.clone())
+        }
+        (UintLe(a, b), UintLt(c, d)) | (UintGe(a, b), UintGt(c, d)) if a == c && b == d => {
+            Some(e2.clone())
+        }
+        (FieldLe(a, b), Not(box FieldEq(e))) if is_not_eq(e, a, b) => {
+            Some(FieldLt(a.clone(), b.clone()))
+        }
+        (FieldGe(a, b), Not(box FieldEq(e))) if is_not_eq(e, a, b) => {
+            Some(FieldGt(a.clone(), b.clone()))
+        }
+        (UintLe(a, b), Not(box UintEq(e))) if is_not_eq(e, a, b) => {
+            Some(UintLt(a.clone(), b.clone()))
+        }
+        (UintGe(a, b), Not(box UintEq(e))) if is_not_eq(e, a, b) => {
+            Some(UintGt(a.clone(), b.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Mark conditionals which select the minimum or the maximum of the operands of their condition,
+/// so that backends with dedicated gadgets can use them
+fn min_max<'ast, T: Field>(
+    c: ConditionalExpression<'ast, T, UExpression<'ast, T>>,
+) -> ConditionalExpression<'ast, T, UExpression<'ast, T>> {
+    // the kinds selecting the smaller and the larger operand of the comparison
+    let (smaller, larger, lower, upper) = match &*c.condition {
+        BooleanExpression::UintLt(a, b) | BooleanExpression::UintLe(a, b) => {
+            (a, b, ConditionalKind::Min, ConditionalKind::Max)
+        }
+        BooleanExpression::UintGt(a, b) | BooleanExpression::UintGe(a, b) => {
+            (a, b, ConditionalKind::Max, ConditionalKind::Min)
+        }
+        _ => return c,
+    };
+
+    let kind = if (&*c.consequence, &*c.alternative) == (&**smaller, &**larger) {
+        lower
+    } else if (&*c.consequence, &*c.alternative) == (&**larger, &**smaller) {
+        upper
+    } else {
+        return c;
+    };
+
+    ConditionalExpression { kind, ..c }
+}
+
+/// Whether `e` is a conditional of kind `kind` (as marked by `min_max`) with `operand` as one of its operands
+fn is_min_max_of<'ast, T: Field>(
+    e: &UExpression<'ast, T>,
+    kind: ConditionalKind,
+    operand: &UExpression<'ast, T>,
+) -> bool {
+    match e.as_inner() {
+        UExpressionInner::Conditional(c) => {
+            c.kind == kind && (*c.consequence == *operand || *c.alternative == *operand)
+        }
+        _ => false,
+    }
+}
+
+/// Hoist a term added in both branches of a conditional out of it, so that `if c then a + x else a + y` becomes
+/// `a + (if c then x else y)`
+fn hoist_common_term<'ast, T: Field>(
+    c: ConditionalExpression<'ast, T, FieldElementExpression<'ast, T>>,
+) -> FieldElementExpression<'ast, T> {
+    let ConditionalExpression {
+        condition,
+        consequence,
+        alternative,
+        kind,
+    } = c;
+
+    // the common term, the remaining operands, and whether the common term comes first
+    let (a, x, y, first) = match (*consequence, *alternative) {
+        (FieldElementExpression::Add(box a, box x), FieldElementExpression::Add(box b, box y))
+            if a == b =>
+        {
+            (a, x, y, true)
+        }
+        (FieldElementExpression::Add(box x, box a), FieldElementExpression::Add(box y, box b))
+            if a == b =>
+        {
+            (a, x, y, false)
+        }
+        (FieldElementExpression::Add(box a, box x), FieldElementExpression::Add(box y, box b))
+        | (FieldElementExpression::Add(box x, box a), FieldElementExpression::Add(box b, box y))
+            if a == b =>
+        {
+            (a, x, y, true)
+        }
+        (consequence, alternative) => {
+            return FieldElementExpression::Conditional(ConditionalExpression::new(
+                *condition,
+                consequence,
+                alternative,
+                kind,
+            ))
+        }
+    };
+
+    let selected =
+        box FieldElementExpression::Conditional(ConditionalExpression::new(*condition, x, y, kind));
+
+    match first {
+        true => FieldElementExpression::Add(box a, selected),
+        false => FieldElementExpression::Add(selected, box a),
+    }
+}
+
+// an upper bound on the value of a uint expression, if its shape gives one
+fn max_value<'ast, T>(e: &UExpressionInner<'ast, T>) -> Option<u128> {
+    match e {
+        UExpressionInner::Value(v) => Some(*v),
+        // the remainder of a division by a non-zero constant is smaller than that constant
+        UExpressionInner::Rem(_, d) => match d.as_inner() {
+            UExpressionInner::Value(d) if *d > 0 => Some(d - 1),
+            _ => None,
+        },
+        // a bitwise and is bounded by each of its operands
+        UExpressionInner::And(l, r) => match (max_value(l.as_inner()), max_value(r.as_inner())) {
+            (Some(l), Some(r)) => Some(l.min(r)),
+            (l, r) => l.or(r),
+        },
+        UExpressionInner::Conditional(c) => Some(std::cmp::max(
+            max_value(c.consequence.as_inner())?,
+            max_value(c.alternative.as_inner())?,
+        )),
+        _ => None,
+    }
+}
+
+// the subtraction of two folded field expressions, simplified without folding its operands again
+fn field_sub<'ast, T: Field>(
+    e1: FieldElementExpression<'ast, T>,
+    e2: FieldElementExpression<'ast, T>,
+) -> FieldElementExpression<'ast, T> {
+    match (e1, e2) {
+        (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+            FieldElementExpression::Number(n1 - n2)
+        }
+        // (x - c1) - c2 == x - (c1 + c2)
+        (
+            FieldElementExpression::Sub(box e, box FieldElementExpression::Number(n1)),
+            FieldElementExpression::Number(n2),
+        ) => FieldElementExpression::Sub(box e, box FieldElementExpression::Number(n1 + n2)),
+        // x - (x - y) == y
+        (e1, FieldElementExpression::Sub(box x, box y)) if e1 == x => y,
+        // (x + y) - y == x
+        (FieldElementExpression::Add(box x, box y), e2) if y == e2 => x,
+        // (y + x) - y == x
+        (FieldElementExpression::Add(box y, box x), e2) if y == e2 => x,
+        (e1, e2) => FieldElementExpression::Sub(box e1, box e2),
+    }
+}
+
 impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
     type Error = Error;
 
@@ -177,32 +449,78 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
     }
 
     fn fold_conditional_expression<
-        E: Expr<'ast, T> + Conditional<'ast, T> + PartialEq + ResultFold<'ast, T>,
+        E: Expr<'ast, T> + PartialEq + Constant + Conditional<'ast, T> + ResultFold<'ast, T>,
     >(
         &mut self,
-        _: &E::Ty,
+        ty: &E::Ty,
         e: ConditionalExpression<'ast, T, E>,
     ) -> Result<ConditionalOrExpression<'ast, T, E>, Self::Error> {
-        Ok(
-            match (
-                self.fold_boolean_expression(*e.condition)?,
-                e.consequence.fold(self)?,
-                e.alternative.fold(self)?,
-            ) {
-                (BooleanExpression::Value(true), consequence, _) => {
-                    ConditionalOrExpression::Expression(consequence.into_inner())
-                }
-                (BooleanExpression::Value(false), _, alternative) => {
-                    ConditionalOrExpression::Expression(alternative.into_inner())
-                }
-                (_, consequence, alternative) if consequence == alternative => {
-                    ConditionalOrExpression::Expression(consequence.into_inner())
+        let condition = self.fold_boolean_expression(*e.condition)?;
+
+        // when the condition is constant, only fold the selected branch, and keep walking
+        // `if c1 then a else if c2 then b else ...` chains without folding the discarded branches
+        match condition {
+            BooleanExpression::Value(true) => {
+                return Ok(ConditionalOrExpression::Expression(
+                    e.consequence.fold(self)?.into_inner(),
+                ))
+            }
+            BooleanExpression::Value(false) => {
+                return match e.alternative.into_conditional() {
+                    Ok(alternative) => self.fold_conditional_expression(ty, alternative),
+                    Err(alternative) => Ok(ConditionalOrExpression::Expression(
+                        alternative.fold(self)?.into_inner(),
+                    )),
                 }
-                (condition, consequence, alternative) => ConditionalOrExpression::Conditional(
-                    ConditionalExpression::new(condition, consequence, alternative, e.kind),
-                ),
-            },
-        )
+            }
+            _ => {}
+        }
+
+        let consequence = e.consequence.fold(self)?;
+        let alternative = e.alternative.fold(self)?;
+
+        // `if c then (if c then x else y) else z` is `if c then x else z`
+        let consequence = match consequence.into_conditional() {
+            Ok(inner) if *inner.condition == condition => *inner.consequence,
+            Ok(inner) => E::conditional(
+                *inner.condition,
+                *inner.consequence,
+                *inner.alternative,
+                inner.kind,
+            ),
+            Err(consequence) => consequence,
+        };
+
+        // `if c then x else (if c then y else z)` is `if c then x else z`
+        let alternative = match alternative.into_conditional() {
+            Ok(inner) if *inner.condition == condition => *inner.alternative,
+            Ok(inner) => E::conditional(
+                *inner.condition,
+                *inner.consequence,
+                *inner.alternative,
+                inner.kind,
+            ),
+            Err(alternative) => alternative,
+        };
+
+        // constant branches may be built differently, for example with spreads, so compare them in canonical form
+        let (consequence, alternative) = if consequence.is_constant() && alternative.is_constant() {
+            (
+                consequence.into_canonical_constant(),
+                alternative.into_canonical_constant(),
+            )
+        } else {
+            (consequence, alternative)
+        };
+
+        Ok(match (condition, consequence, alternative) {
+            (_, consequence, alternative) if consequence == alternative => {
+                ConditionalOrExpression::Expression(consequence.into_inner())
+            }
+            (condition, consequence, alternative) => ConditionalOrExpression::Conditional(
+                ConditionalExpression::new(condition, consequence, alternative, e.kind),
+            ),
+        })
     }
 
     fn fold_assembly_statement(
@@ -286,6 +604,23 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
         &mut self,
         s: TypedStatement<'ast, T>,
     ) -> Result<Vec<TypedStatement<'ast, T>>, Error> {
+        // a redefined identifier is no longer known to be a bit decomposition
+        if let TypedStatement::Definition(assignee, _) = &s {
+            let mut root = assignee;
+            while let TypedAssignee::Select(a, _)
+            | TypedAssignee::Member(a, _)
+            | TypedAssignee::Element(a, _) = root
+            {
+                root = &**a;
+            }
+            if let TypedAssignee::Identifier(v) = root {
+                self.decompositions.remove(&v.id);
+            }
+        }
+
+        // only render the original statement if we need to record it
+        let original = self.audit_log.as_ref().map(|_| s.to_string());
+
         match s {
             TypedStatement::Assembly(statements) => {
                 let statements: Vec<_> = statements
@@ -296,7 +631,10 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     .flatten()
                     .collect();
                 match statements.len() {
-                    0 => Ok(vec![]),
+                    0 => {
+                        self.record_removal(original, "constant assembly constraints");
+                        Ok(vec![])
+                    }
                     _ => Ok(vec![TypedStatement::Assembly(statements)]),
                 }
             }
@@ -324,11 +662,13 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
 
                             assert!(self.constants.insert(var.id, expr).is_none());
 
+                            self.record_removal(original, "constant definition");
                             Ok(vec![])
                         }
                         assignee => match self.try_get_constant_mut(&assignee) {
                             Ok((_, c)) => {
                                 *c = expr.into_canonical_constant();
+                                self.record_removal(original, "constant definition");
                                 Ok(vec![])
                             }
                             Err(v) => match self.constants.remove(&v.id) {
@@ -603,9 +943,49 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                         })
                     }
                     false => {
+                        let decomposition = match embed_call.embed {
+                            FlatEmbed::U64FromBits => Some(FlatEmbed::U64ToBits),
+                            FlatEmbed::U32FromBits => Some(FlatEmbed::U32ToBits),
+                            FlatEmbed::U16FromBits => Some(FlatEmbed::U16ToBits),
+                            FlatEmbed::U8FromBits => Some(FlatEmbed::U8ToBits),
+                            _ => None,
+                        };
+
+                        // recomposing the bits of `x` gives back `x`
+                        let recomposed = match (decomposition, &embed_call.arguments[..]) {
+                            (Some(decomposition), [TypedExpression::Array(a)]) => {
+                                match a.as_inner() {
+                                    ArrayExpressionInner::Identifier(id) => self
+                                        .decompositions
+                                        .get(&id.id)
+                                        .filter(|(embed, _)| *embed == decomposition)
+                                        .map(|(_, x)| x.clone()),
+                                    _ => None,
+                                }
+                            }
+                            _ => None,
+                        };
+
+                        if let (
+                            FlatEmbed::U64ToBits
+                            | FlatEmbed::U32ToBits
+                            | FlatEmbed::U16ToBits
+                            | FlatEmbed::U8ToBits,
+                            TypedAssignee::Identifier(v),
+                        ) = (embed_call.embed, &assignee)
+                        {
+                            self.decompositions.insert(
+                                v.id.clone(),
+                                (embed_call.embed, embed_call.arguments[0].clone()),
+                            );
+                        }
+
                         // if the function arguments are not constant, invalidate the cache
                         // for the return assignees
-                        let def = TypedStatement::Definition(assignee.clone(), embed_call.into());
+                        let def = match recomposed {
+                            Some(x) => TypedStatement::Definition(assignee.clone(), x.into()),
+                            None => TypedStatement::Definition(assignee.clone(), embed_call.into()),
+                        };
 
                         let v = self
                             .try_get_constant_mut(&assignee)
@@ -625,7 +1005,10 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 let expr = self.fold_boolean_expression(e)?;
                 match expr {
                     BooleanExpression::Value(false) => Err(Error::AssertionFailed(err)),
-                    BooleanExpression::Value(true) => Ok(vec![]),
+                    BooleanExpression::Value(true) => {
+                        self.record_removal(original, "constant assertion");
+                        Ok(vec![])
+                    }
                     _ => Ok(vec![TypedStatement::Assertion(expr, err)]),
                 }
             }
@@ -657,6 +1040,15 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                         box UExpressionInner::Value(v).annotate(bitwidth),
                     )),
                 },
+                // a + (-b) == a - b, where neither operand is a value as they would have been folded
+                (e1, UExpressionInner::Neg(box e2)) => {
+                    Ok(UExpressionInner::Sub(box e1.annotate(bitwidth), box e2))
+                }
+                // a + a == 2 * a
+                (e1, e2) if e1 == e2 => Ok(UExpressionInner::Mult(
+                    box UExpressionInner::Value(2).annotate(bitwidth),
+                    box e1.annotate(bitwidth),
+                )),
                 (e1, e2) => Ok(UExpressionInner::Add(
                     box e1.annotate(bitwidth),
                     box e2.annotate(bitwidth),
@@ -699,6 +1091,8 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                         box UExpressionInner::Value(v).annotate(bitwidth),
                     )),
                 },
+                // x - x cannot underflow
+                (e1, e2) if e1 == e2 => Ok(UExpressionInner::Value(0)),
                 (e1, e2) => Ok(UExpressionInner::Sub(
                     box e1.annotate(bitwidth),
                     box e2.annotate(bitwidth),
@@ -737,6 +1131,12 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 }
                 (e, UExpressionInner::Value(v)) => match v {
                     1 => Ok(e),
+                    // x / 2^k == x >> k
+                    v if v.is_power_of_two() => Ok(UExpressionInner::RightShift(
+                        box e.annotate(bitwidth),
+                        box UExpressionInner::Value(v.trailing_zeros() as u128)
+                            .annotate(UBitwidth::B32),
+                    )),
                     _ => Ok(UExpressionInner::Div(
                         box e.annotate(bitwidth),
                         box UExpressionInner::Value(v).annotate(bitwidth),
@@ -758,6 +1158,22 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 }
                 (e, UExpressionInner::Value(v)) => match v {
                     1 => Ok(UExpressionInner::Value(0)),
+                    // x % 2^k == x & (2^k - 1)
+                    v if v.is_power_of_two() => Ok(UExpressionInner::And(
+                        box e.annotate(bitwidth),
+                        box UExpressionInner::Value(v - 1).annotate(bitwidth),
+                    )),
+                    // (x % m) % m == x % m
+                    _ if matches!(
+                        &e,
+                        UExpressionInner::Rem(_, box UExpression {
+                            inner: UExpressionInner::Value(m),
+                            ..
+                        }) if *m == v
+                    ) =>
+                    {
+                        Ok(e)
+                    }
                     _ => Ok(UExpressionInner::Rem(
                         box e.annotate(bitwidth),
                         box UExpressionInner::Value(v).annotate(bitwidth),
@@ -775,6 +1191,7 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     (UExpressionInner::Value(v), UExpressionInner::Value(by)) => {
                         Ok(UExpressionInner::Value(v >> by))
                     }
+                    (e, UExpressionInner::Value(0)) => Ok(e),
                     (e, by) => Ok(UExpressionInner::RightShift(
                         box e.annotate(bitwidth),
                         box by.annotate(UBitwidth::B32),
@@ -788,6 +1205,24 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     (UExpressionInner::Value(v), UExpressionInner::Value(by)) => Ok(
                         UExpressionInner::Value((v << by) & (2_u128.pow(bitwidth as u32) - 1)),
                     ),
+                    (e, UExpressionInner::Value(0)) => Ok(e),
+                    // (x << a) << b == x << (a + b), which is zero if a + b >= bitwidth
+                    (
+                        UExpressionInner::LeftShift(
+                            box e,
+                            box UExpression {
+                                inner: UExpressionInner::Value(a),
+                                ..
+                            },
+                        ),
+                        UExpressionInner::Value(b),
+                    ) => match a + b {
+                        by if by >= bitwidth.to_usize() as u128 => Ok(UExpressionInner::Value(0)),
+                        by => Ok(UExpressionInner::LeftShift(
+                            box e,
+                            box UExpressionInner::Value(by).annotate(UBitwidth::B32),
+                        )),
+                    },
                     (e, by) => Ok(UExpressionInner::LeftShift(
                         box e.annotate(bitwidth),
                         box by.annotate(UBitwidth::B32),
@@ -803,6 +1238,12 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 }
                 (UExpressionInner::Value(0), e2) => Ok(e2),
                 (e1, UExpressionInner::Value(0)) => Ok(e1),
+                // x ^ 0b1...1 == !x
+                (e, UExpressionInner::Value(v)) | (UExpressionInner::Value(v), e)
+                    if v == 2_u128.pow(bitwidth as u32) - 1 =>
+                {
+                    Ok(UExpressionInner::Not(box e.annotate(bitwidth)))
+                }
                 (e1, e2) => {
                     if e1 == e2 {
                         Ok(UExpressionInner::Value(0))
@@ -824,11 +1265,30 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 (UExpressionInner::Value(0), _) | (_, UExpressionInner::Value(0)) => {
                     Ok(UExpressionInner::Value(0))
                 }
+                // every expression fits in its bitwidth, so masking it with all ones is a no-op
+                (UExpressionInner::Value(v), e) | (e, UExpressionInner::Value(v))
+                    if v == 2_u128.pow(bitwidth as u32) - 1 =>
+                {
+                    Ok(e)
+                }
                 (e1, e2) => Ok(UExpressionInner::And(
                     box e1.annotate(bitwidth),
                     box e2.annotate(bitwidth),
                 )),
             },
+            UExpressionInner::Or(box e1, box e2) => match (
+                self.fold_uint_expression(e1)?.into_inner(),
+                self.fold_uint_expression(e2)?.into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    Ok(UExpressionInner::Value(v1 | v2))
+                }
+                (UExpressionInner::Value(0), e) | (e, UExpressionInner::Value(0)) => Ok(e),
+                (e1, e2) => Ok(UExpressionInner::Or(
+                    box e1.annotate(bitwidth),
+                    box e2.annotate(bitwidth),
+                )),
+            },
             UExpressionInner::Not(box e) => {
                 let e = self.fold_uint_expression(e)?.into_inner();
                 match e {
@@ -855,6 +1315,14 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     e => Ok(UExpressionInner::Pos(box e.annotate(bitwidth))),
                 }
             }
+            UExpressionInner::Conditional(c) => {
+                match self.fold_conditional_expression(&bitwidth, c)? {
+                    ConditionalOrExpression::Conditional(c) => {
+                        Ok(UExpressionInner::Conditional(min_max(c)))
+                    }
+                    ConditionalOrExpression::Expression(e) => Ok(e),
+                }
+            }
             e => fold_uint_expression_inner(self, bitwidth, e),
         }
     }
@@ -871,17 +1339,32 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
                     Ok(FieldElementExpression::Number(n1 + n2))
                 }
+                // a + a == 2 * a
+                (e1, e2) if e1 == e2 => Ok(FieldElementExpression::Mult(
+                    box FieldElementExpression::Number(T::from(2)),
+                    box e1,
+                )),
+                // a + (-a) == (-a) + a == 0
+                (e1, FieldElementExpression::Neg(box e2))
+                | (FieldElementExpression::Neg(box e2), e1)
+                    if e1 == e2 =>
+                {
+                    Ok(FieldElementExpression::Number(T::zero()))
+                }
+                // a * c + b * c == (a + b) * c, folded again so that constant factors add up
+                (
+                    FieldElementExpression::Mult(box a, box c1),
+                    FieldElementExpression::Mult(box b, box c2),
+                ) if c1 == c2 => self.fold_field_expression(FieldElementExpression::Mult(
+                    box FieldElementExpression::Add(box a, box b),
+                    box c1,
+                )),
                 (e1, e2) => Ok(FieldElementExpression::Add(box e1, box e2)),
             },
-            FieldElementExpression::Sub(box e1, box e2) => match (
+            FieldElementExpression::Sub(box e1, box e2) => Ok(field_sub(
                 self.fold_field_expression(e1)?,
                 self.fold_field_expression(e2)?,
-            ) {
-                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
-                    Ok(FieldElementExpression::Number(n1 - n2))
-                }
-                (e1, e2) => Ok(FieldElementExpression::Sub(box e1, box e2)),
-            },
+            )),
             FieldElementExpression::Mult(box e1, box e2) => match (
                 self.fold_field_expression(e1)?,
                 self.fold_field_expression(e2)?,
@@ -889,21 +1372,90 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
                     Ok(FieldElementExpression::Number(n1 * n2))
                 }
+                // operands are folded first, so a zero factor anywhere in a product collapses it
+                (FieldElementExpression::Number(n), _) | (_, FieldElementExpression::Number(n))
+                    if n == T::zero() =>
+                {
+                    Ok(FieldElementExpression::Number(T::zero()))
+                }
+                // (-1) * a == -a
+                (FieldElementExpression::Number(n), e) | (e, FieldElementExpression::Number(n))
+                    if n == T::zero() - T::one() =>
+                {
+                    self.fold_field_expression(FieldElementExpression::Neg(box e))
+                }
+                // c * (a + n) == c * a + c * n, distributed over one level only
+                (
+                    FieldElementExpression::Number(c),
+                    FieldElementExpression::Add(box e, box FieldElementExpression::Number(n)),
+                )
+                | (
+                    FieldElementExpression::Number(c),
+                    FieldElementExpression::Add(box FieldElementExpression::Number(n), box e),
+                )
+                | (
+                    FieldElementExpression::Add(box e, box FieldElementExpression::Number(n)),
+                    FieldElementExpression::Number(c),
+                )
+                | (
+                    FieldElementExpression::Add(box FieldElementExpression::Number(n), box e),
+                    FieldElementExpression::Number(c),
+                ) => Ok(FieldElementExpression::Add(
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::Number(c.clone()),
+                        box e,
+                    ),
+                    box FieldElementExpression::Number(c * n),
+                )),
+                // c * (a - n) == c * a - c * n
+                (
+                    FieldElementExpression::Number(c),
+                    FieldElementExpression::Sub(box e, box FieldElementExpression::Number(n)),
+                )
+                | (
+                    FieldElementExpression::Sub(box e, box FieldElementExpression::Number(n)),
+                    FieldElementExpression::Number(c),
+                ) => Ok(FieldElementExpression::Sub(
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::Number(c.clone()),
+                        box e,
+                    ),
+                    box FieldElementExpression::Number(c * n),
+                )),
+                // c * (n - a) == c * n - c * a
+                (
+                    FieldElementExpression::Number(c),
+                    FieldElementExpression::Sub(box FieldElementExpression::Number(n), box e),
+                )
+                | (
+                    FieldElementExpression::Sub(box FieldElementExpression::Number(n), box e),
+                    FieldElementExpression::Number(c),
+                ) => Ok(FieldElementExpression::Sub(
+                    box FieldElementExpression::Number(c.clone() * n),
+                    box FieldElementExpression::Mult(box FieldElementExpression::Number(c), box e),
+                )),
                 (e1, e2) => Ok(FieldElementExpression::Mult(box e1, box e2)),
             },
             FieldElementExpression::Div(box e1, box e2) => match (
                 self.fold_field_expression(e1)?,
                 self.fold_field_expression(e2)?,
             ) {
+                (e, FieldElementExpression::Number(n)) if n == T::zero() => {
+                    Err(Error::DivisionByZero(e.to_string()))
+                }
                 (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
                     Ok(FieldElementExpression::Number(n1 / n2))
                 }
+                // x / 1 == x
+                (e, FieldElementExpression::Number(n)) if n == T::one() => Ok(e),
                 (e1, e2) => Ok(FieldElementExpression::Div(box e1, box e2)),
             },
             FieldElementExpression::Neg(box e) => match self.fold_field_expression(e)? {
                 FieldElementExpression::Number(n) => {
                     Ok(FieldElementExpression::Number(T::zero() - n))
                 }
+                // -(a - b) == b - a
+                FieldElementExpression::Sub(box e1, box e2) => Ok(field_sub(e2, e1)),
                 e => Ok(FieldElementExpression::Neg(box e)),
             },
             FieldElementExpression::Pos(box e) => match self.fold_field_expression(e)? {
@@ -1058,6 +1610,12 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     (e, by) => Ok(FieldElementExpression::RightShift(box e, box by)),
                 }
             }
+            FieldElementExpression::Conditional(c) => {
+                match self.fold_conditional_expression(&Type::FieldElement, c)? {
+                    ConditionalOrExpression::Conditional(c) => Ok(hoist_common_term(c)),
+                    ConditionalOrExpression::Expression(e) => Ok(e),
+                }
+            }
             e => fold_field_expression(self, e),
         }
     }
@@ -1145,6 +1703,18 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                         Err(Error::OutOfBounds(n, size))
                     }
                 }
+                // if all elements are the same constant, any index which is provably in bounds selects it
+                (ArrayExpressionInner::Value(v), i)
+                    if max_value(&i).map(|max| max < size).unwrap_or(false)
+                        && v.0.iter().all(|e| {
+                            matches!(e, TypedExpressionOrSpread::Expression(e) if e.is_constant())
+                        })
+                        && v.0.windows(2).all(|w| w[0] == w[1]) =>
+                {
+                    Ok(SelectOrExpression::Expression(
+                        v.expression_at::<E>(0).unwrap().into_inner(),
+                    ))
+                }
                 (ArrayExpressionInner::Identifier(id), UExpressionInner::Value(n)) => {
                     match self.constants.get(&id.id) {
                         Some(a) => match a {
@@ -1389,10 +1959,18 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 let e1 = self.fold_uint_expression(e1)?;
                 let e2 = self.fold_uint_expression(e2)?;
 
+                let max = 2_u128.pow(e1.bitwidth.to_usize().try_into().unwrap()) - 1;
+
                 match (e1.as_inner(), e2.as_inner()) {
                     (UExpressionInner::Value(n1), UExpressionInner::Value(n2)) => {
                         Ok(BooleanExpression::Value(n1 < n2))
                     }
+                    _ if e1 == e2 => Ok(BooleanExpression::Value(false)),
+                    // x < 0 == false, max < x == false
+                    (_, UExpressionInner::Value(0)) => Ok(BooleanExpression::Value(false)),
+                    (UExpressionInner::Value(n), _) if *n == max => {
+                        Ok(BooleanExpression::Value(false))
+                    }
                     _ => Ok(BooleanExpression::UintLt(box e1, box e2)),
                 }
             }
@@ -1400,10 +1978,24 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                 let e1 = self.fold_uint_expression(e1)?;
                 let e2 = self.fold_uint_expression(e2)?;
 
+                let max = 2_u128.pow(e1.bitwidth.to_usize().try_into().unwrap()) - 1;
+
                 match (e1.as_inner(), e2.as_inner()) {
                     (UExpressionInner::Value(n1), UExpressionInner::Value(n2)) => {
                         Ok(BooleanExpression::Value(n1 <= n2))
                     }
+                    _ if e1 == e2 => Ok(BooleanExpression::Value(true)),
+                    // x <= max == true, 0 <= x == true
+                    (_, UExpressionInner::Value(n)) if *n == max => {
+                        Ok(BooleanExpression::Value(true))
+                    }
+                    (UExpressionInner::Value(0), _) => Ok(BooleanExpression::Value(true)),
+                    // min(a, b) <= a == true, a <= max(a, b) == true
+                    _ if is_min_max_of(&e1, ConditionalKind::Min, &e2)
+                        || is_min_max_of(&e2, ConditionalKind::Max, &e1) =>
+                    {
+                        Ok(BooleanExpression::Value(true))
+                    }
                     _ => Ok(BooleanExpression::UintLe(box e1, box e2)),
                 }
             }
@@ -1426,6 +2018,12 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     (UExpressionInner::Value(n1), UExpressionInner::Value(n2)) => {
                         Ok(BooleanExpression::Value(n1 >= n2))
                     }
+                    // max(a, b) >= a == true, a >= min(a, b) == true
+                    _ if is_min_max_of(&e1, ConditionalKind::Max, &e2)
+                        || is_min_max_of(&e2, ConditionalKind::Min, &e1) =>
+                    {
+                        Ok(BooleanExpression::Value(true))
+                    }
                     _ => Ok(BooleanExpression::UintGe(box e1, box e2)),
                 }
             }
@@ -1446,7 +2044,20 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     (e, BooleanExpression::Value(false)) | (BooleanExpression::Value(false), e) => {
                         Ok(e)
                     }
-                    (e1, e2) => Ok(BooleanExpression::Or(box e1, box e2)),
+                    // x || !x == true
+                    (e1, BooleanExpression::Not(box e2)) | (BooleanExpression::Not(box e2), e1)
+                        if e1 == e2 =>
+                    {
+                        Ok(BooleanExpression::Value(true))
+                    }
+                    // x || (x && y) == x
+                    (e1, BooleanExpression::And(box a, box b))
+                    | (BooleanExpression::And(box a, box b), e1)
+                        if e1 == a || e1 == b =>
+                    {
+                        Ok(e1)
+                    }
+                    (e1, e2) => Ok(normalize_chain(e1, e2, false)),
                 }
             }
             BooleanExpression::And(box e1, box e2) => {
@@ -1466,16 +2077,89 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                     (_, BooleanExpression::Value(false)) | (BooleanExpression::Value(false), _) => {
                         Ok(BooleanExpression::Value(false))
                     }
-                    (e1, e2) => Ok(BooleanExpression::And(box e1, box e2)),
+                    // x && !x == false
+                    (e1, BooleanExpression::Not(box e2)) | (BooleanExpression::Not(box e2), e1)
+                        if e1 == e2 =>
+                    {
+                        Ok(BooleanExpression::Value(false))
+                    }
+                    // x && (x || y) == x
+                    (e1, BooleanExpression::Or(box a, box b))
+                    | (BooleanExpression::Or(box a, box b), e1)
+                        if e1 == a || e1 == b =>
+                    {
+                        Ok(e1)
+                    }
+                    // a <= b && a < b == a < b, a <= b && a != b == a < b
+                    (e1, e2) => Ok(strict_comparison(&e1, &e2)
+                        .or_else(|| strict_comparison(&e2, &e1))
+                        .unwrap_or_else(|| normalize_chain(e1, e2, true))),
                 }
             }
+            BooleanExpression::BoolEq(e) => match self.fold_eq_expression(e)? {
+                EqOrBoolean::Boolean(e) => Ok(e),
+                EqOrBoolean::Eq(EqExpression {
+                    left: box BooleanExpression::Value(v),
+                    right: box e,
+                })
+                | EqOrBoolean::Eq(EqExpression {
+                    left: box e,
+                    right: box BooleanExpression::Value(v),
+                }) => match v {
+                    // b == true == b
+                    true => Ok(e),
+                    // b == false == !b
+                    false => self.fold_boolean_expression(BooleanExpression::Not(box e)),
+                },
+                EqOrBoolean::Eq(e) => Ok(BooleanExpression::BoolEq(e)),
+            },
             BooleanExpression::Not(box e) => {
                 let e = self.fold_boolean_expression(e)?;
                 match e {
                     BooleanExpression::Value(v) => Ok(BooleanExpression::Value(!v)),
+                    // !!x == x, so that `x != false`, which is `x ^ false`, reduces to `x`
+                    BooleanExpression::Not(box e) => Ok(e),
+                    // push the negation into comparisons
+                    BooleanExpression::FieldLt(e1, e2) => Ok(BooleanExpression::FieldGe(e1, e2)),
+                    BooleanExpression::FieldLe(e1, e2) => Ok(BooleanExpression::FieldGt(e1, e2)),
+                    BooleanExpression::FieldGt(e1, e2) => Ok(BooleanExpression::FieldLe(e1, e2)),
+                    BooleanExpression::FieldGe(e1, e2) => Ok(BooleanExpression::FieldLt(e1, e2)),
+                    BooleanExpression::UintLt(e1, e2) => Ok(BooleanExpression::UintGe(e1, e2)),
+                    BooleanExpression::UintLe(e1, e2) => Ok(BooleanExpression::UintGt(e1, e2)),
+                    BooleanExpression::UintGt(e1, e2) => Ok(BooleanExpression::UintLe(e1, e2)),
+                    BooleanExpression::UintGe(e1, e2) => Ok(BooleanExpression::UintLt(e1, e2)),
                     e => Ok(BooleanExpression::Not(box e)),
                 }
             }
+            BooleanExpression::Conditional(c) => {
+                match self.fold_conditional_expression(&Type::Boolean, c)? {
+                    ConditionalOrExpression::Conditional(c) => {
+                        match (*c.condition, *c.consequence, *c.alternative) {
+                            // if c then c else false == c
+                            (condition, consequence, BooleanExpression::Value(false))
+                                if consequence == condition =>
+                            {
+                                Ok(condition)
+                            }
+                            // if c then true else c == c
+                            (condition, BooleanExpression::Value(true), alternative)
+                                if alternative == condition =>
+                            {
+                                Ok(condition)
+                            }
+                            (condition, consequence, alternative) => {
+                                Ok(BooleanExpression::Conditional(ConditionalExpression::new(
+                                    condition,
+                                    consequence,
+                                    alternative,
+                                    c.kind,
+                                )))
+                            }
+                        }
+                    }
+                    ConditionalOrExpression::Expression(e) => Ok(e),
+                }
+            }
             e => fold_boolean_expression(self, e),
         }
     }
@@ -1508,60 +2192,357 @@ mod tests {
             }
 
             #[test]
-            fn sub() {
-                let e = FieldElementExpression::Sub(
-                    box FieldElementExpression::Number(Bn128Field::from(3)),
-                    box FieldElementExpression::Number(Bn128Field::from(2)),
+            fn add_self() {
+                let e = FieldElementExpression::Add(
+                    box FieldElementExpression::identifier("a".into()),
+                    box FieldElementExpression::identifier("a".into()),
                 );
 
                 assert_eq!(
-                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
-                    Ok(FieldElementExpression::Number(Bn128Field::from(1)))
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Mult(
+                        box FieldElementExpression::Number(Bn128Field::from(2)),
+                        box FieldElementExpression::identifier("a".into()),
+                    ))
                 );
             }
 
             #[test]
-            fn mult() {
-                let e = FieldElementExpression::Mult(
-                    box FieldElementExpression::Number(Bn128Field::from(3)),
-                    box FieldElementExpression::Number(Bn128Field::from(2)),
+            fn add_neg() {
+                let x = || FieldElementExpression::<Bn128Field>::identifier("x".into());
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_field_expression(x() + FieldElementExpression::Neg(box x())),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(0)))
                 );
 
                 assert_eq!(
-                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
-                    Ok(FieldElementExpression::Number(Bn128Field::from(6)))
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_field_expression(FieldElementExpression::Neg(box x()) + x()),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(0)))
                 );
             }
 
             #[test]
-            fn div() {
-                let e = FieldElementExpression::Div(
-                    box FieldElementExpression::Number(Bn128Field::from(6)),
-                    box FieldElementExpression::Number(Bn128Field::from(2)),
+            fn add_common_factor() {
+                // x * k + y * k
+                let e = FieldElementExpression::Add(
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::identifier("x".into()),
+                        box FieldElementExpression::identifier("k".into()),
+                    ),
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::identifier("y".into()),
+                        box FieldElementExpression::identifier("k".into()),
+                    ),
                 );
 
                 assert_eq!(
-                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
-                    Ok(FieldElementExpression::Number(Bn128Field::from(3)))
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Mult(
+                        box FieldElementExpression::Add(
+                            box FieldElementExpression::identifier("x".into()),
+                            box FieldElementExpression::identifier("y".into()),
+                        ),
+                        box FieldElementExpression::identifier("k".into()),
+                    ))
+                );
+
+                // 2 * k + 3 * k
+                let e = FieldElementExpression::Add(
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::Number(Bn128Field::from(2)),
+                        box FieldElementExpression::identifier("k".into()),
+                    ),
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::Number(Bn128Field::from(3)),
+                        box FieldElementExpression::identifier("k".into()),
+                    ),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Mult(
+                        box FieldElementExpression::Number(Bn128Field::from(5)),
+                        box FieldElementExpression::identifier("k".into()),
+                    ))
                 );
             }
 
             #[test]
-            fn pow() {
-                let e = FieldElementExpression::Pow(
+            fn sub() {
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::Number(Bn128Field::from(3)),
                     box FieldElementExpression::Number(Bn128Field::from(2)),
-                    box 3u32.into(),
                 );
 
                 assert_eq!(
                     Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
-                    Ok(FieldElementExpression::Number(Bn128Field::from(8)))
+                    Ok(FieldElementExpression::Number(Bn128Field::from(1)))
                 );
             }
 
             #[test]
-            fn left_shift() {
-                let mut constants = Constants::new();
+            fn sub_constants() {
+                // (a - 3) - 4
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::Sub(
+                        box FieldElementExpression::identifier("a".into()),
+                        box FieldElementExpression::Number(Bn128Field::from(3)),
+                    ),
+                    box FieldElementExpression::Number(Bn128Field::from(4)),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Sub(
+                        box FieldElementExpression::identifier("a".into()),
+                        box FieldElementExpression::Number(Bn128Field::from(7)),
+                    ))
+                );
+            }
+
+            #[test]
+            fn sub_nested() {
+                // a - (a - b)
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::identifier("a".into()),
+                    box FieldElementExpression::Sub(
+                        box FieldElementExpression::identifier("a".into()),
+                        box FieldElementExpression::identifier("b".into()),
+                    ),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::identifier("b".into()))
+                );
+
+                // a - (c - b)
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::identifier("a".into()),
+                    box FieldElementExpression::Sub(
+                        box FieldElementExpression::identifier("c".into()),
+                        box FieldElementExpression::identifier("b".into()),
+                    ),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e.clone()),
+                    Ok(e)
+                );
+            }
+
+            #[test]
+            fn sub_cancel_add() {
+                let x = || FieldElementExpression::identifier("x".into());
+                let y = || FieldElementExpression::identifier("y".into());
+                let z = || FieldElementExpression::identifier("z".into());
+
+                // (x + y) - y
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::Add(box x(), box y()),
+                    box y(),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(x())
+                );
+
+                // (y + x) - y
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::Add(box y(), box x()),
+                    box y(),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(x())
+                );
+
+                // (x + y) - z
+                let e = FieldElementExpression::Sub(
+                    box FieldElementExpression::Add(box x(), box y()),
+                    box z(),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e.clone()),
+                    Ok(e)
+                );
+            }
+
+            #[test]
+            fn mult() {
+                let e = FieldElementExpression::Mult(
+                    box FieldElementExpression::Number(Bn128Field::from(3)),
+                    box FieldElementExpression::Number(Bn128Field::from(2)),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(6)))
+                );
+            }
+
+            #[test]
+            fn neg_pos_zero() {
+                let zero = || FieldElementExpression::Number(Bn128Field::from(0));
+
+                for e in vec![
+                    FieldElementExpression::Neg(box zero()),
+                    FieldElementExpression::Pos(box zero()),
+                    FieldElementExpression::Neg(box FieldElementExpression::Neg(box zero())),
+                    FieldElementExpression::Pos(box FieldElementExpression::Neg(box zero())),
+                    FieldElementExpression::Neg(box FieldElementExpression::Sub(
+                        box zero(),
+                        box zero(),
+                    )),
+                ] {
+                    assert_eq!(
+                        Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                        Ok(zero())
+                    );
+                }
+            }
+
+            #[test]
+            fn mult_distribute() {
+                let x = || FieldElementExpression::<Bn128Field>::identifier("x".into());
+
+                // 2 * (x + 3) == 2 * x + 6
+                let e = FieldElementExpression::Mult(
+                    box FieldElementExpression::Number(Bn128Field::from(2)),
+                    box FieldElementExpression::Add(
+                        box x(),
+                        box FieldElementExpression::Number(Bn128Field::from(3)),
+                    ),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Add(
+                        box FieldElementExpression::Mult(
+                            box FieldElementExpression::Number(Bn128Field::from(2)),
+                            box x()
+                        ),
+                        box FieldElementExpression::Number(Bn128Field::from(6)),
+                    ))
+                );
+            }
+
+            #[test]
+            fn mult_minus_one() {
+                let e = FieldElementExpression::Mult(
+                    box FieldElementExpression::Number(Bn128Field::from(0) - Bn128Field::from(1)),
+                    box FieldElementExpression::identifier("a".into()),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Neg(
+                        box FieldElementExpression::identifier("a".into())
+                    ))
+                );
+            }
+
+            #[test]
+            fn mult_zero_factor() {
+                // a * (b * 0)
+                let e = FieldElementExpression::Mult(
+                    box FieldElementExpression::identifier("a".into()),
+                    box FieldElementExpression::Mult(
+                        box FieldElementExpression::identifier("b".into()),
+                        box FieldElementExpression::Number(Bn128Field::from(0)),
+                    ),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(0)))
+                );
+            }
+
+            #[test]
+            fn div_one() {
+                let e = FieldElementExpression::Div(
+                    box FieldElementExpression::identifier("a".into()),
+                    box FieldElementExpression::Number(Bn128Field::from(1)),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::identifier("a".into()))
+                );
+            }
+
+            #[test]
+            fn div_self() {
+                let e = FieldElementExpression::Div(
+                    box FieldElementExpression::Number(Bn128Field::from(5)),
+                    box FieldElementExpression::Number(Bn128Field::from(5)),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(1)))
+                );
+            }
+
+            #[test]
+            fn div() {
+                let e = FieldElementExpression::Div(
+                    box FieldElementExpression::Number(Bn128Field::from(6)),
+                    box FieldElementExpression::Number(Bn128Field::from(2)),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(3)))
+                );
+            }
+
+            #[test]
+            fn div_zero() {
+                let e = FieldElementExpression::Div(
+                    box FieldElementExpression::identifier("x".into()),
+                    box FieldElementExpression::Number(Bn128Field::from(0)),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Err(Error::DivisionByZero("x".into()))
+                );
+            }
+
+            #[test]
+            fn pow() {
+                let e = FieldElementExpression::Pow(
+                    box FieldElementExpression::Number(Bn128Field::from(2)),
+                    box 3u32.into(),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(8)))
+                );
+            }
+
+            #[test]
+            fn left_shift() {
+                let mut constants = Constants::new();
                 let mut propagator = Propagator::with_constants(&mut constants);
 
                 assert_eq!(
@@ -1666,6 +2647,35 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn neg_sub() {
+                let e = FieldElementExpression::Neg(box FieldElementExpression::Sub(
+                    box FieldElementExpression::Number(Bn128Field::from(5)),
+                    box FieldElementExpression::Number(Bn128Field::from(2)),
+                ));
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(
+                        Bn128Field::from(2) - Bn128Field::from(5)
+                    ))
+                );
+
+                let e = FieldElementExpression::Neg(box FieldElementExpression::Sub(
+                    box FieldElementExpression::identifier("x".into()),
+                    box FieldElementExpression::identifier("y".into()),
+                ));
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Sub(
+                        box FieldElementExpression::identifier("y".into()),
+                        box FieldElementExpression::identifier("x".into()),
+                    ))
+                );
+            }
+
             #[test]
             fn if_else_true() {
                 let e = FieldElementExpression::conditional(
@@ -1697,77 +2707,402 @@ mod tests {
             }
 
             #[test]
-            fn select() {
-                let e = FieldElementExpression::select(
-                    ArrayExpressionInner::Value(
-                        vec![
-                            FieldElementExpression::Number(Bn128Field::from(1)).into(),
-                            FieldElementExpression::Number(Bn128Field::from(2)).into(),
-                            FieldElementExpression::Number(Bn128Field::from(3)).into(),
-                        ]
-                        .into(),
-                    )
-                    .annotate(Type::FieldElement, 3u32),
-                    UExpressionInner::Add(box 1u32.into(), box 1u32.into())
-                        .annotate(UBitwidth::B32),
+            fn if_else_identical_branches() {
+                let e = FieldElementExpression::conditional(
+                    BooleanExpression::identifier("c".into()),
+                    FieldElementExpression::identifier("a".into()),
+                    FieldElementExpression::identifier("a".into()),
+                    ConditionalKind::IfElse,
                 );
 
                 assert_eq!(
                     Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
-                    Ok(FieldElementExpression::Number(Bn128Field::from(3)))
+                    Ok(FieldElementExpression::identifier("a".into()))
                 );
             }
-        }
-
-        #[cfg(test)]
-        mod boolean {
-            use super::*;
 
             #[test]
-            fn not() {
-                let e_true: BooleanExpression<Bn128Field> =
-                    BooleanExpression::Not(box BooleanExpression::Value(false));
-
-                let e_false: BooleanExpression<Bn128Field> =
-                    BooleanExpression::Not(box BooleanExpression::Value(true));
+            fn if_else_equal_constant_branches() {
+                let n = |v| FieldElementExpression::Number(Bn128Field::from(v));
+                let array =
+                    |size: u32, values: Vec<TypedExpressionOrSpread<'static, Bn128Field>>| {
+                        ArrayExpressionInner::Value(values.into())
+                            .annotate(Type::FieldElement, size)
+                    };
 
-                let e_default: BooleanExpression<Bn128Field> =
-                    BooleanExpression::Not(box BooleanExpression::identifier("a".into()));
+                // if c then [...[1], 2] else [1, 2] == [1, 2]
+                let e = ArrayExpression::conditional(
+                    BooleanExpression::identifier("c".into()),
+                    array(
+                        2,
+                        vec![
+                            TypedExpressionOrSpread::Spread(array(1, vec![n(1).into()]).into()),
+                            n(2).into(),
+                        ],
+                    ),
+                    array(2, vec![n(1).into(), n(2).into()]),
+                    ConditionalKind::IfElse,
+                );
 
                 assert_eq!(
-                    Propagator::with_constants(&mut Constants::new())
-                        .fold_boolean_expression(e_true),
-                    Ok(BooleanExpression::Value(true))
+                    Propagator::with_constants(&mut Constants::new()).fold_array_expression(e),
+                    Ok(array(2, vec![n(1).into(), n(2).into()]))
                 );
-                assert_eq!(
-                    Propagator::with_constants(&mut Constants::new())
-                        .fold_boolean_expression(e_false),
-                    Ok(BooleanExpression::Value(false))
+
+                // if c then [...[1; 2]] else [1, 1] == [1, 1]
+                let e = ArrayExpression::conditional(
+                    BooleanExpression::identifier("c".into()),
+                    array(
+                        2,
+                        vec![TypedExpressionOrSpread::Spread(
+                            ArrayExpressionInner::Repeat(box n(1).into(), box 2u32.into())
+                                .annotate(Type::FieldElement, 2u32)
+                                .into(),
+                        )],
+                    ),
+                    array(2, vec![n(1).into(), n(1).into()]),
+                    ConditionalKind::IfElse,
                 );
+
                 assert_eq!(
-                    Propagator::with_constants(&mut Constants::new())
-                        .fold_boolean_expression(e_default.clone()),
-                    Ok(e_default)
+                    Propagator::with_constants(&mut Constants::new()).fold_array_expression(e),
+                    Ok(array(2, vec![n(1).into(), n(1).into()]))
                 );
             }
 
             #[test]
-            fn field_eq() {
-                let e_constant_true = BooleanExpression::FieldEq(EqExpression::new(
-                    FieldElementExpression::Number(Bn128Field::from(2)),
-                    FieldElementExpression::Number(Bn128Field::from(2)),
-                ));
+            fn if_else_common_term() {
+                let c = || BooleanExpression::identifier("c".into());
+                let a = || FieldElementExpression::identifier("a".into());
+                let n = |v| FieldElementExpression::Number(Bn128Field::from(v));
 
-                let e_constant_false = BooleanExpression::FieldEq(EqExpression::new(
-                    FieldElementExpression::Number(Bn128Field::from(4)),
-                    FieldElementExpression::Number(Bn128Field::from(2)),
-                ));
+                // if c then a + 1 else a + 2 == a + (if c then 1 else 2)
+                let e = FieldElementExpression::conditional(
+                    c(),
+                    FieldElementExpression::Add(box a(), box n(1)),
+                    FieldElementExpression::Add(box a(), box n(2)),
+                    ConditionalKind::IfElse,
+                );
 
-                let e_identifier_true: BooleanExpression<Bn128Field> =
-                    BooleanExpression::FieldEq(EqExpression::new(
-                        FieldElementExpression::identifier("a".into()),
-                        FieldElementExpression::identifier("a".into()),
-                    ));
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Add(
+                        box a(),
+                        box FieldElementExpression::conditional(
+                            c(),
+                            n(1),
+                            n(2),
+                            ConditionalKind::IfElse
+                        )
+                    ))
+                );
+
+                // if c then 1 + a else a + 2 == a + (if c then 1 else 2)
+                let e = FieldElementExpression::conditional(
+                    c(),
+                    FieldElementExpression::Add(box n(1), box a()),
+                    FieldElementExpression::Add(box a(), box n(2)),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::Add(
+                        box a(),
+                        box FieldElementExpression::conditional(
+                            c(),
+                            n(1),
+                            n(2),
+                            ConditionalKind::IfElse
+                        )
+                    ))
+                );
+
+                // if c then a + 1 else b + 2 is left untouched
+                let e = FieldElementExpression::conditional(
+                    c(),
+                    FieldElementExpression::Add(box a(), box n(1)),
+                    FieldElementExpression::Add(
+                        box FieldElementExpression::identifier("b".into()),
+                        box n(2),
+                    ),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e.clone()),
+                    Ok(e)
+                );
+            }
+
+            #[test]
+            fn if_else_nested_same_condition() {
+                let c = || BooleanExpression::identifier("c".into());
+                let x = || FieldElementExpression::identifier("x".into());
+                let y = || FieldElementExpression::identifier("y".into());
+                let z = || FieldElementExpression::identifier("z".into());
+
+                // if c then x else (if c then y else z) == if c then x else z
+                let e = FieldElementExpression::conditional(
+                    c(),
+                    x(),
+                    FieldElementExpression::conditional(c(), y(), z(), ConditionalKind::IfElse),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::conditional(
+                        c(),
+                        x(),
+                        z(),
+                        ConditionalKind::IfElse
+                    ))
+                );
+
+                // if c then (if c then x else y) else z == if c then x else z
+                let e = FieldElementExpression::conditional(
+                    c(),
+                    FieldElementExpression::conditional(c(), x(), y(), ConditionalKind::IfElse),
+                    z(),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::conditional(
+                        c(),
+                        x(),
+                        z(),
+                        ConditionalKind::IfElse
+                    ))
+                );
+            }
+
+            #[test]
+            fn if_else_chain() {
+                let c = |name: &'static str| BooleanExpression::identifier(name.into());
+                let x = |name: &'static str| {
+                    FieldElementExpression::<Bn128Field>::identifier(name.into())
+                };
+
+                // if c1 then a else if true then b else if c3 then c else d == if c1 then a else b
+                let e = FieldElementExpression::conditional(
+                    c("c1"),
+                    x("a"),
+                    FieldElementExpression::conditional(
+                        BooleanExpression::Value(true),
+                        x("b"),
+                        FieldElementExpression::conditional(
+                            c("c3"),
+                            x("c"),
+                            x("d"),
+                            ConditionalKind::IfElse,
+                        ),
+                        ConditionalKind::IfElse,
+                    ),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::conditional(
+                        c("c1"),
+                        x("a"),
+                        x("b"),
+                        ConditionalKind::IfElse
+                    ))
+                );
+
+                // if false then a else if false then b else if true then c else d == c
+                let e = FieldElementExpression::conditional(
+                    BooleanExpression::Value(false),
+                    x("a"),
+                    FieldElementExpression::conditional(
+                        BooleanExpression::Value(false),
+                        x("b"),
+                        FieldElementExpression::conditional(
+                            BooleanExpression::Value(true),
+                            x("c"),
+                            x("d"),
+                            ConditionalKind::IfElse,
+                        ),
+                        ConditionalKind::IfElse,
+                    ),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(x("c"))
+                );
+            }
+
+            #[test]
+            fn if_else_constant_comparison() {
+                // the condition is folded to a value before a branch is selected
+                let e = FieldElementExpression::conditional(
+                    BooleanExpression::FieldLt(
+                        box FieldElementExpression::Number(Bn128Field::from(5)),
+                        box FieldElementExpression::Number(Bn128Field::from(3)),
+                    ),
+                    FieldElementExpression::identifier("a".into()),
+                    FieldElementExpression::identifier("b".into()),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_field_expression(e),
+                    Ok(FieldElementExpression::identifier("b".into()))
+                );
+            }
+
+            #[test]
+            fn select() {
+                let e = FieldElementExpression::select(
+                    ArrayExpressionInner::Value(
+                        vec![
+                            FieldElementExpression::Number(Bn128Field::from(1)).into(),
+                            FieldElementExpression::Number(Bn128Field::from(2)).into(),
+                            FieldElementExpression::Number(Bn128Field::from(3)).into(),
+                        ]
+                        .into(),
+                    )
+                    .annotate(Type::FieldElement, 3u32),
+                    UExpressionInner::Add(box 1u32.into(), box 1u32.into())
+                        .annotate(UBitwidth::B32),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(3)))
+                );
+            }
+
+            #[test]
+            fn select_shifted_index() {
+                let array = ArrayExpressionInner::Value(
+                    vec![
+                        FieldElementExpression::Number(Bn128Field::from(1)).into(),
+                        FieldElementExpression::Number(Bn128Field::from(2)).into(),
+                        FieldElementExpression::Number(Bn128Field::from(3)).into(),
+                    ]
+                    .into(),
+                )
+                .annotate(Type::FieldElement, 3u32);
+
+                // array[4 >> 1]
+                let e = FieldElementExpression::select(
+                    array.clone(),
+                    UExpressionInner::RightShift(box 4u32.into(), box 1u32.into())
+                        .annotate(UBitwidth::B32),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(3)))
+                );
+
+                // array[(1 << 3 | 1) & 3]
+                let e = FieldElementExpression::select(
+                    array,
+                    UExpressionInner::And(
+                        box UExpressionInner::Or(
+                            box UExpressionInner::LeftShift(box 1u32.into(), box 3u32.into())
+                                .annotate(UBitwidth::B32),
+                            box 1u32.into(),
+                        )
+                        .annotate(UBitwidth::B32),
+                        box 3u32.into(),
+                    )
+                    .annotate(UBitwidth::B32),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(2)))
+                );
+            }
+        }
+
+        #[cfg(test)]
+        mod boolean {
+            use super::*;
+
+            #[test]
+            fn not() {
+                let e_true: BooleanExpression<Bn128Field> =
+                    BooleanExpression::Not(box BooleanExpression::Value(false));
+
+                let e_false: BooleanExpression<Bn128Field> =
+                    BooleanExpression::Not(box BooleanExpression::Value(true));
+
+                let e_default: BooleanExpression<Bn128Field> =
+                    BooleanExpression::Not(box BooleanExpression::identifier("a".into()));
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e_true),
+                    Ok(BooleanExpression::Value(true))
+                );
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e_false),
+                    Ok(BooleanExpression::Value(false))
+                );
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e_default.clone()),
+                    Ok(e_default)
+                );
+            }
+
+            #[test]
+            fn double_not() {
+                let a = || BooleanExpression::<Bn128Field>::identifier("a".into());
+
+                // !!a == a
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(
+                        BooleanExpression::Not(box BooleanExpression::Not(box a()))
+                    ),
+                    Ok(a())
+                );
+
+                // !!!a == !a
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(
+                        BooleanExpression::Not(box BooleanExpression::Not(
+                            box BooleanExpression::Not(box a())
+                        ))
+                    ),
+                    Ok(BooleanExpression::Not(box a()))
+                );
+            }
+
+            #[test]
+            fn field_eq() {
+                let e_constant_true = BooleanExpression::FieldEq(EqExpression::new(
+                    FieldElementExpression::Number(Bn128Field::from(2)),
+                    FieldElementExpression::Number(Bn128Field::from(2)),
+                ));
+
+                let e_constant_false = BooleanExpression::FieldEq(EqExpression::new(
+                    FieldElementExpression::Number(Bn128Field::from(4)),
+                    FieldElementExpression::Number(Bn128Field::from(2)),
+                ));
+
+                let e_identifier_true: BooleanExpression<Bn128Field> =
+                    BooleanExpression::FieldEq(EqExpression::new(
+                        FieldElementExpression::identifier("a".into()),
+                        FieldElementExpression::identifier("a".into()),
+                    ));
 
                 let e_identifier_unchanged: BooleanExpression<Bn128Field> =
                     BooleanExpression::FieldEq(EqExpression::new(
@@ -1836,6 +3171,62 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn bool_eq_literal() {
+                let b = || BooleanExpression::<Bn128Field>::identifier("b".into());
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::BoolEq(EqExpression::new(
+                            b(),
+                            BooleanExpression::Value(true)
+                        ))),
+                    Ok(b())
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::BoolEq(EqExpression::new(
+                            b(),
+                            BooleanExpression::Value(false)
+                        ))),
+                    Ok(BooleanExpression::Not(box b()))
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::BoolEq(EqExpression::new(
+                            BooleanExpression::Value(true),
+                            b()
+                        ))),
+                    Ok(b())
+                );
+            }
+
+            #[test]
+            fn bool_eq_true_nested() {
+                // ((a == b) == true) == true
+                let a_eq_b = || {
+                    BooleanExpression::FieldEq(EqExpression::new(
+                        FieldElementExpression::<Bn128Field>::identifier("a".into()),
+                        FieldElementExpression::identifier("b".into()),
+                    ))
+                };
+
+                let e = BooleanExpression::BoolEq(EqExpression::new(
+                    BooleanExpression::BoolEq(EqExpression::new(
+                        a_eq_b(),
+                        BooleanExpression::Value(true),
+                    )),
+                    BooleanExpression::Value(true),
+                ));
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(e),
+                    Ok(a_eq_b())
+                );
+            }
+
             #[test]
             fn array_eq() {
                 let e_constant_true = BooleanExpression::ArrayEq(EqExpression::new(
@@ -1964,6 +3355,55 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn array_eq_spreads_on_both_sides() {
+                let spread = |v: usize| {
+                    TypedExpressionOrSpread::Spread(
+                        ArrayExpressionInner::Value(
+                            vec![TypedExpressionOrSpread::Expression(
+                                FieldElementExpression::Number(Bn128Field::from(v)).into(),
+                            )]
+                            .into(),
+                        )
+                        .annotate(Type::FieldElement, 1u32)
+                        .into(),
+                    )
+                };
+
+                let expression = |v: usize| {
+                    TypedExpressionOrSpread::Expression(
+                        FieldElementExpression::Number(Bn128Field::from(v)).into(),
+                    )
+                };
+
+                // [...[1], 2] == [1, ...[2]]
+                let e_true = BooleanExpression::ArrayEq(EqExpression::new(
+                    ArrayExpressionInner::Value(vec![spread(1), expression(2)].into())
+                        .annotate(Type::FieldElement, 2u32),
+                    ArrayExpressionInner::Value(vec![expression(1), spread(2)].into())
+                        .annotate(Type::FieldElement, 2u32),
+                ));
+
+                // [...[1], 2] == [2, ...[1]]
+                let e_false = BooleanExpression::ArrayEq(EqExpression::new(
+                    ArrayExpressionInner::Value(vec![spread(1), expression(2)].into())
+                        .annotate(Type::FieldElement, 2u32),
+                    ArrayExpressionInner::Value(vec![expression(2), spread(1)].into())
+                        .annotate(Type::FieldElement, 2u32),
+                ));
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e_true),
+                    Ok(BooleanExpression::Value(true))
+                );
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e_false),
+                    Ok(BooleanExpression::Value(false))
+                );
+            }
+
             #[test]
             fn lt() {
                 let e_true = BooleanExpression::FieldLt(
@@ -2013,10 +3453,76 @@ mod tests {
             }
 
             #[test]
-            fn gt() {
-                let e_true = BooleanExpression::FieldGt(
-                    box FieldElementExpression::Number(Bn128Field::from(5)),
-                    box FieldElementExpression::Number(Bn128Field::from(4)),
+            fn uint_lt_self() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+
+                let e = BooleanExpression::UintLt(box a.clone(), box a);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(e),
+                    Ok(BooleanExpression::Value(false))
+                );
+            }
+
+            #[test]
+            fn uint_le_self() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+
+                let e = BooleanExpression::UintLe(box a.clone(), box a);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(e),
+                    Ok(BooleanExpression::Value(true))
+                );
+            }
+
+            #[test]
+            fn uint_extreme_values() {
+                let x: UExpression<Bn128Field> =
+                    UExpression::identifier("x".into()).annotate(UBitwidth::B32);
+
+                // x <= 0xffffffff
+                let e = BooleanExpression::UintLe(
+                    box x.clone(),
+                    box UExpressionInner::Value(0xffffffff).annotate(UBitwidth::B32),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(e),
+                    Ok(BooleanExpression::Value(true))
+                );
+
+                // x < 0
+                let e = BooleanExpression::UintLt(
+                    box x.clone(),
+                    box UExpressionInner::Value(0).annotate(UBitwidth::B32),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(e),
+                    Ok(BooleanExpression::Value(false))
+                );
+
+                // x <= 0xfffffffe is left as is
+                let e = BooleanExpression::UintLe(
+                    box x,
+                    box UExpressionInner::Value(0xfffffffe).annotate(UBitwidth::B32),
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e.clone()),
+                    Ok(e)
+                );
+            }
+
+            #[test]
+            fn gt() {
+                let e_true = BooleanExpression::FieldGt(
+                    box FieldElementExpression::Number(Bn128Field::from(5)),
+                    box FieldElementExpression::Number(Bn128Field::from(4)),
                 );
 
                 let e_false = BooleanExpression::FieldGt(
@@ -2199,6 +3705,915 @@ mod tests {
                     Ok(BooleanExpression::Value(false))
                 );
             }
+
+            #[test]
+            fn chain() {
+                let a = || BooleanExpression::identifier("a".into());
+                let b = || BooleanExpression::identifier("b".into());
+
+                // a && (true && (b && (a && true))) == a && b
+                let e = BooleanExpression::And(
+                    box a(),
+                    box BooleanExpression::And(
+                        box BooleanExpression::Value(true),
+                        box BooleanExpression::And(
+                            box b(),
+                            box BooleanExpression::And(box a(), box BooleanExpression::Value(true)),
+                        ),
+                    ),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e),
+                    Ok(BooleanExpression::And(box a(), box b()))
+                );
+
+                // (a || b) || (false || a) == a || b
+                let e = BooleanExpression::Or(
+                    box BooleanExpression::Or(box a(), box b()),
+                    box BooleanExpression::Or(box BooleanExpression::Value(false), box a()),
+                );
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e),
+                    Ok(BooleanExpression::Or(box a(), box b()))
+                );
+            }
+
+            #[test]
+            fn xor() {
+                let a = || BooleanExpression::identifier("a".into());
+                let b = || BooleanExpression::identifier("b".into());
+                let value = BooleanExpression::Value;
+
+                // boolean xor is expressed as `x != y`
+                let xor = |x: BooleanExpression<'static, Bn128Field>, y| {
+                    BooleanExpression::Not(box BooleanExpression::BoolEq(EqExpression::new(x, y)))
+                };
+
+                let fold = |e| {
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e)
+                };
+
+                // literals
+                assert_eq!(fold(xor(value(true), value(true))), Ok(value(false)));
+                assert_eq!(fold(xor(value(false), value(false))), Ok(value(false)));
+                assert_eq!(fold(xor(value(true), value(false))), Ok(value(true)));
+
+                // mixed
+                assert_eq!(
+                    fold(xor(a(), value(true))),
+                    Ok(BooleanExpression::Not(box a()))
+                );
+                assert_eq!(fold(xor(a(), value(false))), Ok(a()));
+                assert_eq!(fold(xor(value(false), a())), Ok(a()));
+
+                // identifiers
+                assert_eq!(fold(xor(a(), a())), Ok(value(false)));
+                assert_eq!(fold(xor(a(), b())), Ok(xor(a(), b())));
+            }
+
+            #[test]
+            fn conditional_on_branch() {
+                let c = || BooleanExpression::identifier("c".into());
+
+                // if c then c else false == c
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::conditional(
+                            c(),
+                            c(),
+                            BooleanExpression::Value(false),
+                            ConditionalKind::IfElse
+                        )),
+                    Ok(c())
+                );
+                // if c then true else c == c
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::conditional(
+                            c(),
+                            BooleanExpression::Value(true),
+                            c(),
+                            ConditionalKind::IfElse
+                        )),
+                    Ok(c())
+                );
+            }
+
+            #[test]
+            fn strict_comparison() {
+                let a = || box FieldElementExpression::<Bn128Field>::identifier("a".into());
+                let b = || box FieldElementExpression::identifier("b".into());
+
+                // (a <= b) && (a < b) == a < b
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(
+                        BooleanExpression::And(
+                            box BooleanExpression::FieldLe(a(), b()),
+                            box BooleanExpression::FieldLt(a(), b())
+                        )
+                    ),
+                    Ok(BooleanExpression::FieldLt(a(), b()))
+                );
+                // (a != b) && (a <= b) == a < b
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_boolean_expression(
+                        BooleanExpression::And(
+                            box BooleanExpression::Not(box BooleanExpression::FieldEq(
+                                EqExpression::new(*a(), *b())
+                            )),
+                            box BooleanExpression::FieldLe(a(), b())
+                        )
+                    ),
+                    Ok(BooleanExpression::FieldLt(a(), b()))
+                );
+            }
+
+            #[test]
+            fn absorption() {
+                let a = || BooleanExpression::identifier("a".into());
+                let b = || BooleanExpression::identifier("b".into());
+
+                // a && (a || b) == a
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::And(
+                            box a(),
+                            box BooleanExpression::Or(box a(), box b())
+                        )),
+                    Ok(a())
+                );
+                // a || (a && b) == a
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::Or(
+                            box a(),
+                            box BooleanExpression::And(box a(), box b())
+                        )),
+                    Ok(a())
+                );
+                // (b && a) || a == a
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::Or(
+                            box BooleanExpression::And(box b(), box a()),
+                            box a()
+                        )),
+                    Ok(a())
+                );
+            }
+
+            #[test]
+            fn not_comparison() {
+                let a = || box FieldElementExpression::identifier("a".into());
+                let b = || box FieldElementExpression::identifier("b".into());
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::Not(
+                            box BooleanExpression::FieldLt(a(), b())
+                        )),
+                    Ok(BooleanExpression::FieldGe(a(), b()))
+                );
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::Not(
+                            box BooleanExpression::FieldLe(a(), b())
+                        )),
+                    Ok(BooleanExpression::FieldGt(a(), b()))
+                );
+            }
+
+            #[test]
+            fn complement() {
+                let a = || BooleanExpression::identifier("a".into());
+
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::And(
+                            box a(),
+                            box BooleanExpression::Not(box a()),
+                        )),
+                    Ok(BooleanExpression::Value(false))
+                );
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::Or(
+                            box BooleanExpression::Not(box a()),
+                            box a(),
+                        )),
+                    Ok(BooleanExpression::Value(true))
+                );
+            }
+        }
+
+        #[cfg(test)]
+        mod uint {
+            use super::*;
+
+            #[test]
+            fn add_self() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone() + a.clone()),
+                    Ok(UExpressionInner::Mult(box 2u32.into(), box a).annotate(UBitwidth::B32))
+                );
+            }
+
+            #[test]
+            fn add_neg() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+                let b: UExpression<Bn128Field> =
+                    UExpression::identifier("b".into()).annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone() + (-b.clone())),
+                    Ok(a - b)
+                );
+            }
+
+            #[test]
+            fn rem_power_of_two() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone() % 8u32.into()),
+                    Ok(a.clone().and(7u32.into()))
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone() % 6u32.into()),
+                    Ok(a % 6u32.into())
+                );
+            }
+
+            #[test]
+            fn div_power_of_two() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone() / 4u32.into()),
+                    Ok(a.clone().right_shift(2u32.into()))
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone() / 5u32.into()),
+                    Ok(a / 5u32.into())
+                );
+            }
+
+            #[test]
+            fn xor_all_ones() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B8);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.clone().xor(255u8.into())),
+                    Ok(!a)
+                );
+            }
+
+            #[test]
+            fn left_shift_by_zero() {
+                let e: UExpression<Bn128Field> = UExpression::identifier("a".into())
+                    .annotate(UBitwidth::B32)
+                    .left_shift(0u32.into());
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(e),
+                    Ok(UExpression::identifier("a".into()).annotate(UBitwidth::B32))
+                );
+            }
+
+            #[test]
+            fn left_shift_twice() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(
+                        a.clone().left_shift(2u32.into()).left_shift(3u32.into())
+                    ),
+                    Ok(a.clone().left_shift(5u32.into()))
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(a.left_shift(20u32.into()).left_shift(12u32.into())),
+                    Ok(UExpression::from(0u32))
+                );
+            }
+
+            #[test]
+            fn floor_sub() {
+                let value = |v: u32| -> UExpression<Bn128Field> { v.into() };
+                let floor_sub = |e1: UExpression<Bn128Field>, e2: UExpression<Bn128Field>| {
+                    UExpressionInner::FloorSub(box e1, box e2).annotate(UBitwidth::B32)
+                };
+                let x = UExpression::identifier("x".into()).annotate(UBitwidth::B32);
+
+                // 10 - 3 does not underflow, so it is the same as a regular subtraction
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(floor_sub(value(10), value(3))),
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(
+                        UExpressionInner::Sub(box value(10), box value(3)).annotate(UBitwidth::B32)
+                    )
+                );
+
+                // 3 - 10 saturates
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(floor_sub(value(3), value(10))),
+                    Ok(value(0))
+                );
+
+                // x - x is zero
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(floor_sub(x.clone(), x.clone())),
+                    Ok(value(0))
+                );
+
+                // x - 10 may underflow
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(floor_sub(x.clone(), value(10))),
+                    Ok(floor_sub(x, value(10)))
+                );
+            }
+
+            #[test]
+            fn min_max() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+                let b: UExpression<Bn128Field> =
+                    UExpression::identifier("b".into()).annotate(UBitwidth::B32);
+                let lt = || BooleanExpression::UintLt(box a.clone(), box b.clone());
+
+                // a < b ? a : b
+                let e =
+                    UExpression::conditional(lt(), a.clone(), b.clone(), ConditionalKind::Ternary);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(e),
+                    Ok(UExpression::conditional(
+                        lt(),
+                        a.clone(),
+                        b.clone(),
+                        ConditionalKind::Min
+                    ))
+                );
+
+                // if a < b { b } else { a }
+                let e =
+                    UExpression::conditional(lt(), b.clone(), a.clone(), ConditionalKind::IfElse);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(e),
+                    Ok(UExpression::conditional(
+                        lt(),
+                        b.clone(),
+                        a.clone(),
+                        ConditionalKind::Max
+                    ))
+                );
+
+                // c ? a : b is left as is
+                let e = UExpression::conditional(
+                    BooleanExpression::identifier("c".into()),
+                    a,
+                    b,
+                    ConditionalKind::Ternary,
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression(e.clone()),
+                    Ok(e)
+                );
+            }
+
+            #[test]
+            fn min_max_comparisons() {
+                let a: UExpression<Bn128Field> =
+                    UExpression::identifier("a".into()).annotate(UBitwidth::B32);
+                let b: UExpression<Bn128Field> =
+                    UExpression::identifier("b".into()).annotate(UBitwidth::B32);
+                let lt = || BooleanExpression::UintLt(box a.clone(), box b.clone());
+
+                let min =
+                    UExpression::conditional(lt(), a.clone(), b.clone(), ConditionalKind::Ternary);
+                let max =
+                    UExpression::conditional(lt(), b.clone(), a.clone(), ConditionalKind::Ternary);
+
+                // (a < b ? a : b) <= b
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::UintLe(
+                            box min.clone(),
+                            box b.clone()
+                        )),
+                    Ok(BooleanExpression::Value(true))
+                );
+
+                // a >= (a < b ? a : b)
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::UintGe(
+                            box a.clone(),
+                            box min.clone()
+                        )),
+                    Ok(BooleanExpression::Value(true))
+                );
+
+                // (a < b ? b : a) >= a
+                assert_eq!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(BooleanExpression::UintGe(
+                            box max.clone(),
+                            box a.clone()
+                        )),
+                    Ok(BooleanExpression::Value(true))
+                );
+
+                // (a < b ? a : b) >= a is not always true
+                let e = BooleanExpression::UintGe(box min, box a);
+                assert_ne!(
+                    Propagator::<Bn128Field>::with_constants(&mut Constants::new())
+                        .fold_boolean_expression(e),
+                    Ok(BooleanExpression::Value(true))
+                );
+            }
+
+            #[test]
+            fn left_shift_full_mask() {
+                // (x << 2) & 0xffffffff == x << 2
+                let x: UExpression<Bn128Field> =
+                    UExpression::identifier("x".into()).annotate(UBitwidth::B32);
+
+                let e = UExpressionInner::And(
+                    box x.clone().left_shift(2u32.into()),
+                    box 0xffffffffu32.into(),
+                )
+                .annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(e),
+                    Ok(x.left_shift(2u32.into()))
+                );
+            }
+
+            #[test]
+            fn right_shift_by_zero() {
+                let e: UExpression<Bn128Field> = UExpression::identifier("a".into())
+                    .annotate(UBitwidth::B32)
+                    .right_shift(0u32.into());
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_uint_expression(e),
+                    Ok(UExpression::identifier("a".into()).annotate(UBitwidth::B32))
+                );
+            }
+
+            #[test]
+            fn rem_idempotent() {
+                let x: UExpression<Bn128Field> =
+                    UExpression::identifier("x".into()).annotate(UBitwidth::B32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression((x.clone() % 7u32.into()) % 7u32.into()),
+                    Ok(x.clone() % 7u32.into())
+                );
+
+                // different moduli are kept
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new())
+                        .fold_uint_expression((x.clone() % 7u32.into()) % 5u32.into()),
+                    Ok((x % 7u32.into()) % 5u32.into())
+                );
+            }
+        }
+
+        #[cfg(test)]
+        mod array {
+            use super::*;
+
+            #[test]
+            fn if_else_identical_branches() {
+                let array = || {
+                    ArrayExpressionInner::Value(
+                        vec![
+                            FieldElementExpression::identifier("a".into()).into(),
+                            FieldElementExpression::Number(Bn128Field::from(2)).into(),
+                        ]
+                        .into(),
+                    )
+                    .annotate(Type::FieldElement, 2u32)
+                };
+
+                let e = ArrayExpression::conditional(
+                    BooleanExpression::identifier("c".into()),
+                    array(),
+                    array(),
+                    ConditionalKind::IfElse,
+                );
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_array_expression(e),
+                    Ok(array())
+                );
+            }
+
+            #[test]
+            fn select_two_dimensions() {
+                let row = |a: u32, b: u32| -> ArrayExpression<Bn128Field> {
+                    ArrayExpressionInner::Value(
+                        vec![
+                            FieldElementExpression::Number(Bn128Field::from(a)).into(),
+                            FieldElementExpression::Number(Bn128Field::from(b)).into(),
+                        ]
+                        .into(),
+                    )
+                    .annotate(Type::FieldElement, 2u32)
+                };
+
+                // [[1, 2], [3, 4]][1][0]
+                let matrix =
+                    ArrayExpressionInner::Value(vec![row(1, 2).into(), row(3, 4).into()].into())
+                        .annotate(Type::array((Type::FieldElement, 2u32)), 2u32);
+
+                let e = FieldElementExpression::select(ArrayExpression::select(matrix, 1u32), 0u32);
+
+                assert_eq!(
+                    Propagator::with_constants(&mut Constants::new()).fold_field_expression(e),
+                    Ok(FieldElementExpression::Number(Bn128Field::from(3)))
+                );
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod statement {
+        use super::*;
+        use zokrates_ast::untyped::Position;
+
+        #[test]
+        fn audit_constant_assertion() {
+            let s: TypedStatement<Bn128Field> = TypedStatement::Assertion(
+                BooleanExpression::Value(true),
+                RuntimeError::SelectRangeCheck,
+            );
+
+            let mut constants = Constants::new();
+            let mut propagator = Propagator::with_constants(&mut constants).with_audit_log();
+
+            assert_eq!(propagator.fold_statement(s.clone()), Ok(vec![]));
+            assert_eq!(
+                propagator.audit_log(),
+                Some(&vec![(s.to_string(), "constant assertion".to_string())])
+            );
+        }
+
+        #[test]
+        fn constant_struct_member() {
+            // struct Foo { field a; field b; }
+            let ty = StructType::new(
+                "".into(),
+                "Foo".into(),
+                vec![],
+                vec![
+                    StructMember::new("a".into(), Type::FieldElement),
+                    StructMember::new("b".into(), Type::FieldElement),
+                ],
+            );
+
+            // Foo s = Foo { a: 1, b: 2 };
+            let definition: TypedStatement<Bn128Field> = TypedStatement::definition(
+                Variable::struc("s", ty.clone()).into(),
+                StructExpressionInner::Value(vec![
+                    FieldElementExpression::Number(Bn128Field::from(1)).into(),
+                    FieldElementExpression::Number(Bn128Field::from(2)).into(),
+                ])
+                .annotate(ty.clone())
+                .into(),
+            );
+
+            // return s.b;
+            let ret = TypedStatement::Return(
+                FieldElementExpression::member(
+                    StructExpression::identifier("s".into()).annotate(ty),
+                    "b".into(),
+                )
+                .into(),
+            );
+
+            let mut constants = Constants::new();
+            let mut propagator = Propagator::with_constants(&mut constants);
+
+            // the struct is never built, only the value of the member is kept
+            assert_eq!(propagator.fold_statement(definition), Ok(vec![]));
+            assert_eq!(
+                propagator.fold_statement(ret),
+                Ok(vec![TypedStatement::Return(
+                    FieldElementExpression::Number(Bn128Field::from(2)).into()
+                )])
+            );
+        }
+
+        #[test]
+        fn select_identical_elements() {
+            // return [7, 7, 7][i % 3]
+            let i = UExpression::identifier("i".into()).annotate(UBitwidth::B32);
+
+            let select = |index: UExpression<'static, Bn128Field>| {
+                TypedStatement::Return(
+                    FieldElementExpression::select(
+                        ArrayExpressionInner::Value(
+                            vec![
+                                FieldElementExpression::Number(Bn128Field::from(7)).into(),
+                                FieldElementExpression::Number(Bn128Field::from(7)).into(),
+                                FieldElementExpression::Number(Bn128Field::from(7)).into(),
+                            ]
+                            .into(),
+                        )
+                        .annotate(Type::FieldElement, 3u32),
+                        index,
+                    )
+                    .into(),
+                )
+            };
+
+            assert_eq!(
+                Propagator::with_constants(&mut Constants::new())
+                    .fold_statement(select(i.clone() % 3u32.into())),
+                Ok(vec![TypedStatement::Return(
+                    FieldElementExpression::Number(Bn128Field::from(7)).into()
+                )])
+            );
+
+            // return [7, 7, 7][i], which may be out of bounds, so the select is kept
+            assert_eq!(
+                Propagator::with_constants(&mut Constants::new()).fold_statement(select(i.clone())),
+                Ok(vec![select(i)])
+            );
+        }
+
+        #[test]
+        fn program() {
+            use zokrates_ast::typed::utils::{f, TypedProgramBuilder};
+
+            // def main(field a) -> field {
+            //     field b = 1 + 2;
+            //     return a + b;
+            // }
+            let a = || FieldElementExpression::<Bn128Field>::identifier("a".into());
+
+            let p = TypedProgramBuilder::new()
+                .argument(DeclarationVariable::field_element("a"))
+                .output(DeclarationType::FieldElement)
+                .statement(TypedStatement::definition(
+                    Variable::field_element("b").into(),
+                    (f(1) + f(2)).into(),
+                ))
+                .statement(TypedStatement::Return(
+                    (a() + FieldElementExpression::identifier("b".into())).into(),
+                ))
+                .build();
+
+            let expected = TypedProgramBuilder::new()
+                .argument(DeclarationVariable::field_element("a"))
+                .output(DeclarationType::FieldElement)
+                .statement(TypedStatement::Return((a() + f(3)).into()))
+                .build();
+
+            assert_eq!(Propagator::propagate(p), Ok(expected));
+        }
+
+        #[test]
+        fn program_stats() {
+            use zokrates_ast::typed::utils::{f, TypedProgramBuilder};
+
+            // def main() -> field {
+            //     field a = 1;
+            //     field b = 2;
+            //     field c = a + b;
+            //     return c;
+            // }
+            let p = TypedProgramBuilder::<Bn128Field>::new()
+                .output(DeclarationType::FieldElement)
+                .statement(TypedStatement::definition(
+                    Variable::field_element("a").into(),
+                    f(1).into(),
+                ))
+                .statement(TypedStatement::definition(
+                    Variable::field_element("b").into(),
+                    f(2).into(),
+                ))
+                .statement(TypedStatement::definition(
+                    Variable::field_element("c").into(),
+                    (FieldElementExpression::identifier("a".into())
+                        + FieldElementExpression::identifier("b".into()))
+                    .into(),
+                ))
+                .statement(TypedStatement::Return(
+                    FieldElementExpression::identifier("c".into()).into(),
+                ))
+                .build();
+
+            let expected = TypedProgramBuilder::new()
+                .output(DeclarationType::FieldElement)
+                .statement(TypedStatement::Return(f(3).into()))
+                .build();
+
+            assert_eq!(Propagator::propagate_with_stats(p), Ok((expected, 3)));
+        }
+
+        #[test]
+        fn bits_round_trip() {
+            // bool[32] bits = u32_to_bits(x);
+            // u32 y = u32_from_bits(bits);
+            let x = UExpression::identifier("x".into()).annotate(UBitwidth::B32);
+
+            let mut constants = Constants::new();
+            let mut propagator = Propagator::<Bn128Field>::with_constants(&mut constants);
+
+            let to_bits = TypedStatement::embed_call_definition(
+                Variable::array("bits", Type::Boolean, 32u32).into(),
+                EmbedCall::new(FlatEmbed::U32ToBits, vec![], vec![x.clone().into()]),
+            );
+
+            assert_eq!(
+                propagator.fold_statement(to_bits.clone()),
+                Ok(vec![to_bits])
+            );
+
+            let from_bits = TypedStatement::embed_call_definition(
+                Variable::uint("y", UBitwidth::B32).into(),
+                EmbedCall::new(
+                    FlatEmbed::U32FromBits,
+                    vec![],
+                    vec![ArrayExpression::identifier("bits".into())
+                        .annotate(Type::Boolean, 32u32)
+                        .into()],
+                ),
+            );
+
+            assert_eq!(
+                propagator.fold_statement(from_bits),
+                Ok(vec![TypedStatement::definition(
+                    Variable::uint("y", UBitwidth::B32).into(),
+                    x.into()
+                )])
+            );
+        }
+
+        #[test]
+        fn constant_casts() {
+            let u = |v: u128, bitwidth: UBitwidth| UExpressionInner::Value(v).annotate(bitwidth);
+            let bits = |id: &'static str, size: u32| {
+                ArrayExpression::identifier(id.into()).annotate(Type::Boolean, size)
+            };
+
+            // narrowing: bool[32] bits = u32_to_bits(0x12345678); u8 y = u8_from_bits(bits[24..32]);
+            let mut constants = Constants::new();
+            let mut propagator = Propagator::<Bn128Field>::with_constants(&mut constants);
+
+            let to_bits = TypedStatement::embed_call_definition(
+                Variable::array("bits", Type::Boolean, 32u32).into(),
+                EmbedCall::new(
+                    FlatEmbed::U32ToBits,
+                    vec![],
+                    vec![u(0x12345678, UBitwidth::B32).into()],
+                ),
+            );
+
+            let from_bits = TypedStatement::embed_call_definition(
+                Variable::uint("y", UBitwidth::B8).into(),
+                EmbedCall::new(
+                    FlatEmbed::U8FromBits,
+                    vec![],
+                    vec![ArrayExpressionInner::Slice(
+                        box bits("bits", 32),
+                        box 24u32.into(),
+                        box 32u32.into(),
+                    )
+                    .annotate(Type::Boolean, 8u32)
+                    .into()],
+                ),
+            );
+
+            let ret = TypedStatement::Return(
+                UExpression::identifier("y".into())
+                    .annotate(UBitwidth::B8)
+                    .into(),
+            );
+
+            assert_eq!(propagator.fold_statement(to_bits), Ok(vec![]));
+            assert_eq!(propagator.fold_statement(from_bits), Ok(vec![]));
+            // the low bits are kept
+            assert_eq!(
+                propagator.fold_statement(ret),
+                Ok(vec![TypedStatement::Return(u(0x78, UBitwidth::B8).into())])
+            );
+
+            // widening: bool[8] bits = u8_to_bits(0xab); u32 y = u32_from_bits([...[false; 24], ...bits]);
+            let mut constants = Constants::new();
+            let mut propagator = Propagator::<Bn128Field>::with_constants(&mut constants);
+
+            let to_bits = TypedStatement::embed_call_definition(
+                Variable::array("bits", Type::Boolean, 8u32).into(),
+                EmbedCall::new(
+                    FlatEmbed::U8ToBits,
+                    vec![],
+                    vec![u(0xab, UBitwidth::B8).into()],
+                ),
+            );
+
+            let from_bits = TypedStatement::embed_call_definition(
+                Variable::uint("y", UBitwidth::B32).into(),
+                EmbedCall::new(
+                    FlatEmbed::U32FromBits,
+                    vec![],
+                    vec![ArrayExpressionInner::Value(
+                        vec![
+                            TypedExpressionOrSpread::Spread(
+                                ArrayExpressionInner::Repeat(
+                                    box BooleanExpression::Value(false).into(),
+                                    box 24u32.into(),
+                                )
+                                .annotate(Type::Boolean, 24u32)
+                                .into(),
+                            ),
+                            TypedExpressionOrSpread::Spread(bits("bits", 8).into()),
+                        ]
+                        .into(),
+                    )
+                    .annotate(Type::Boolean, 32u32)
+                    .into()],
+                ),
+            );
+
+            let ret = TypedStatement::Return(
+                UExpression::identifier("y".into())
+                    .annotate(UBitwidth::B32)
+                    .into(),
+            );
+
+            assert_eq!(propagator.fold_statement(to_bits), Ok(vec![]));
+            assert_eq!(propagator.fold_statement(from_bits), Ok(vec![]));
+            // the high bits are zero
+            assert_eq!(
+                propagator.fold_statement(ret),
+                Ok(vec![TypedStatement::Return(u(0xab, UBitwidth::B32).into())])
+            );
+        }
+
+        #[test]
+        fn failed_assertion_source() {
+            let metadata = SourceMetadata::new("main.zok".into(), Position { line: 3, col: 5 });
+
+            let s: TypedStatement<Bn128Field> = TypedStatement::Assertion(
+                BooleanExpression::Value(false),
+                RuntimeError::SourceAssertion(metadata.clone()),
+            );
+
+            let error = Propagator::with_constants(&mut Constants::new())
+                .fold_statement(s)
+                .unwrap_err();
+
+            assert_eq!(error.source_metadata(), Some(&metadata));
+        }
+
+        #[test]
+        fn no_audit_by_default() {
+            let s: TypedStatement<Bn128Field> = TypedStatement::Assertion(
+                BooleanExpression::Value(true),
+                RuntimeError::SelectRangeCheck,
+            );
+
+            let mut constants = Constants::new();
+            let mut propagator = Propagator::with_constants(&mut constants);
+
+            assert_eq!(propagator.fold_statement(s), Ok(vec![]));
+            assert_eq!(propagator.audit_log(), None);
         }
     }
 }
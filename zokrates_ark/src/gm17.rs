@@ -13,7 +13,7 @@ use rand_0_8::{CryptoRng, RngCore};
 use zokrates_ast::ir::{ProgIterator, Statement, Witness};
 use zokrates_proof_systems::gm17::{ProofPoints, VerificationKey, GM17};
 use zokrates_proof_systems::Scheme;
-use zokrates_proof_systems::{Backend, NonUniversalBackend, Proof, SetupKeypair};
+use zokrates_proof_systems::{Backend, BackendError, NonUniversalBackend, Proof, SetupKeypair};
 
 impl<T: Field + ArkFieldExtensions> NonUniversalBackend<T, GM17> for Ark {
     fn setup<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
@@ -40,12 +40,12 @@ impl<T: Field + ArkFieldExtensions> NonUniversalBackend<T, GM17> for Ark {
 }
 
 impl<T: Field + ArkFieldExtensions> Backend<T, GM17> for Ark {
-    fn generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
+    fn try_generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
         program: ProgIterator<'a, T, I>,
         witness: Witness<T>,
         proving_key: Vec<u8>,
         rng: &mut R,
-    ) -> Proof<T, GM17> {
+    ) -> Result<Proof<T, GM17>, BackendError> {
         let computation = Computation::with_witness(program, witness);
 
         let inputs = computation
@@ -57,36 +57,40 @@ impl<T: Field + ArkFieldExtensions> Backend<T, GM17> for Ark {
         let pk = ProvingKey::<<T as ArkFieldExtensions>::ArkEngine>::deserialize_unchecked(
             &mut proving_key.as_slice(),
         )
-        .unwrap();
+        .map_err(|e| BackendError::ProvingKey(e.to_string()))?;
 
-        let proof = ArkGM17::<T::ArkEngine>::prove(&pk, computation, rng).unwrap();
+        let proof = ArkGM17::<T::ArkEngine>::prove(&pk, computation, rng)
+            .map_err(|e| BackendError::Backend(e.to_string()))?;
         let proof_points = ProofPoints {
             a: parse_g1::<T>(&proof.a),
             b: parse_g2::<T>(&proof.b),
             c: parse_g1::<T>(&proof.c),
         };
 
-        Proof::new(proof_points, inputs)
+        Ok(Proof::new(proof_points, inputs))
     }
 
-    fn verify(vk: <GM17 as Scheme<T>>::VerificationKey, proof: Proof<T, GM17>) -> bool {
+    fn try_verify(
+        vk: <GM17 as Scheme<T>>::VerificationKey,
+        proof: Proof<T, GM17>,
+    ) -> Result<bool, BackendError> {
         let vk = VerifyingKey {
-            h_g2: serialization::to_g2::<T>(vk.h),
-            g_alpha_g1: serialization::to_g1::<T>(vk.g_alpha),
-            h_beta_g2: serialization::to_g2::<T>(vk.h_beta),
-            g_gamma_g1: serialization::to_g1::<T>(vk.g_gamma),
-            h_gamma_g2: serialization::to_g2::<T>(vk.h_gamma),
+            h_g2: serialization::to_g2::<T>(vk.h)?,
+            g_alpha_g1: serialization::to_g1::<T>(vk.g_alpha)?,
+            h_beta_g2: serialization::to_g2::<T>(vk.h_beta)?,
+            g_gamma_g1: serialization::to_g1::<T>(vk.g_gamma)?,
+            h_gamma_g2: serialization::to_g2::<T>(vk.h_gamma)?,
             query: vk
                 .query
                 .into_iter()
                 .map(serialization::to_g1::<T>)
-                .collect(),
+                .collect::<Result<_, _>>()?,
         };
 
         let ark_proof = ArkProof {
-            a: serialization::to_g1::<T>(proof.proof.a),
-            b: serialization::to_g2::<T>(proof.proof.b),
-            c: serialization::to_g1::<T>(proof.proof.c),
+            a: serialization::to_g1::<T>(proof.proof.a)?,
+            b: serialization::to_g2::<T>(proof.proof.b)?,
+            c: serialization::to_g1::<T>(proof.proof.c)?,
         };
 
         let pvk: PreparedVerifyingKey<<T as ArkFieldExtensions>::ArkEngine> =
@@ -95,14 +99,11 @@ impl<T: Field + ArkFieldExtensions> Backend<T, GM17> for Ark {
         let public_inputs: Vec<_> = proof
             .inputs
             .iter()
-            .map(|s| {
-                T::try_from_str(s.trim_start_matches("0x"), 16)
-                    .unwrap()
-                    .into_ark()
-            })
-            .collect::<Vec<_>>();
+            .map(|s| serialization::to_fr::<T>(s))
+            .collect::<Result<_, _>>()?;
 
-        verify_proof(&pvk, &ark_proof, &public_inputs).unwrap()
+        verify_proof(&pvk, &ark_proof, &public_inputs)
+            .map_err(|e| BackendError::Backend(e.to_string()))
     }
 }
 
@@ -164,4 +165,29 @@ mod tests {
 
         assert!(ans);
     }
+
+    #[test]
+    fn malformed_proving_key() {
+        let program: Prog<Bls12_377Field> = Prog {
+            arguments: vec![Parameter::public(Variable::new(0))],
+            return_count: 1,
+            statements: vec![Statement::constraint(Variable::new(0), Variable::public(0))],
+        };
+
+        let rng = &mut StdRng::from_entropy();
+        let interpreter = Interpreter::default();
+
+        let witness = interpreter
+            .execute(program.clone(), &[Bls12_377Field::from(42)])
+            .unwrap();
+
+        let res = <Ark as Backend<Bls12_377Field, GM17>>::try_generate_proof(
+            program,
+            witness,
+            vec![0u8; 4],
+            rng,
+        );
+
+        assert!(matches!(res, Err(BackendError::ProvingKey(_))));
+    }
 }
@@ -6,7 +6,7 @@ use ark_groth16::{
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use zokrates_field::ArkFieldExtensions;
 use zokrates_field::Field;
-use zokrates_proof_systems::{Backend, NonUniversalBackend, Proof, SetupKeypair};
+use zokrates_proof_systems::{Backend, BackendError, NonUniversalBackend, Proof, SetupKeypair};
 
 use crate::Computation;
 use crate::{parse_fr, serialization, Ark};
@@ -17,12 +17,12 @@ use zokrates_proof_systems::groth16::{ProofPoints, VerificationKey, G16};
 use zokrates_proof_systems::Scheme;
 
 impl<T: Field + ArkFieldExtensions> Backend<T, G16> for Ark {
-    fn generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
+    fn try_generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
         program: ProgIterator<'a, T, I>,
         witness: Witness<T>,
         proving_key: Vec<u8>,
         rng: &mut R,
-    ) -> Proof<T, G16> {
+    ) -> Result<Proof<T, G16>, BackendError> {
         let computation = Computation::with_witness(program, witness);
 
         let inputs = computation
@@ -34,49 +34,50 @@ impl<T: Field + ArkFieldExtensions> Backend<T, G16> for Ark {
         let pk = ProvingKey::<<T as ArkFieldExtensions>::ArkEngine>::deserialize_unchecked(
             &mut proving_key.as_slice(),
         )
-        .unwrap();
+        .map_err(|e| BackendError::ProvingKey(e.to_string()))?;
 
-        let proof = Groth16::<T::ArkEngine>::prove(&pk, computation, rng).unwrap();
+        let proof = Groth16::<T::ArkEngine>::prove(&pk, computation, rng)
+            .map_err(|e| BackendError::Backend(e.to_string()))?;
         let proof_points = ProofPoints {
             a: parse_g1::<T>(&proof.a),
             b: parse_g2::<T>(&proof.b),
             c: parse_g1::<T>(&proof.c),
         };
 
-        Proof::new(proof_points, inputs)
+        Ok(Proof::new(proof_points, inputs))
     }
 
-    fn verify(vk: <G16 as Scheme<T>>::VerificationKey, proof: Proof<T, G16>) -> bool {
+    fn try_verify(
+        vk: <G16 as Scheme<T>>::VerificationKey,
+        proof: Proof<T, G16>,
+    ) -> Result<bool, BackendError> {
         let vk = VerifyingKey {
-            alpha_g1: serialization::to_g1::<T>(vk.alpha),
-            beta_g2: serialization::to_g2::<T>(vk.beta),
-            gamma_g2: serialization::to_g2::<T>(vk.gamma),
-            delta_g2: serialization::to_g2::<T>(vk.delta),
+            alpha_g1: serialization::to_g1::<T>(vk.alpha)?,
+            beta_g2: serialization::to_g2::<T>(vk.beta)?,
+            gamma_g2: serialization::to_g2::<T>(vk.gamma)?,
+            delta_g2: serialization::to_g2::<T>(vk.delta)?,
             gamma_abc_g1: vk
                 .gamma_abc
                 .into_iter()
                 .map(serialization::to_g1::<T>)
-                .collect(),
+                .collect::<Result<_, _>>()?,
         };
 
         let pvk: PreparedVerifyingKey<T::ArkEngine> = prepare_verifying_key(&vk);
         let ark_proof = ArkProof {
-            a: serialization::to_g1::<T>(proof.proof.a),
-            b: serialization::to_g2::<T>(proof.proof.b),
-            c: serialization::to_g1::<T>(proof.proof.c),
+            a: serialization::to_g1::<T>(proof.proof.a)?,
+            b: serialization::to_g2::<T>(proof.proof.b)?,
+            c: serialization::to_g1::<T>(proof.proof.c)?,
         };
 
         let public_inputs: Vec<_> = proof
             .inputs
             .iter()
-            .map(|s| {
-                T::try_from_str(s.trim_start_matches("0x"), 16)
-                    .unwrap()
-                    .into_ark()
-            })
-            .collect::<Vec<_>>();
+            .map(|s| serialization::to_fr::<T>(s))
+            .collect::<Result<_, _>>()?;
 
-        verify_proof(&pvk, &ark_proof, &public_inputs).unwrap()
+        verify_proof(&pvk, &ark_proof, &public_inputs)
+            .map_err(|e| BackendError::Backend(e.to_string()))
     }
 }
 
@@ -161,4 +162,32 @@ mod tests {
 
         assert!(ans);
     }
+
+    #[test]
+    fn malformed_proof() {
+        let program: Prog<Bls12_377Field> = Prog {
+            arguments: vec![Parameter::public(Variable::new(0))],
+            return_count: 1,
+            statements: vec![Statement::constraint(Variable::new(0), Variable::public(0))],
+        };
+
+        let rng = &mut StdRng::from_entropy();
+        let keypair =
+            <Ark as NonUniversalBackend<Bls12_377Field, G16>>::setup(program.clone(), rng);
+        let interpreter = Interpreter::default();
+
+        let witness = interpreter
+            .execute(program.clone(), &[Bls12_377Field::from(42)])
+            .unwrap();
+
+        let mut proof = <Ark as Backend<Bls12_377Field, G16>>::generate_proof(
+            program, witness, keypair.pk, rng,
+        );
+        proof.inputs[0] = "0xzz".to_string();
+
+        assert!(matches!(
+            <Ark as Backend<Bls12_377Field, G16>>::try_verify(keypair.vk, proof),
+            Err(BackendError::Encoding(_))
+        ));
+    }
 }
@@ -243,43 +243,62 @@ mod parse {
 pub mod serialization {
     use ark_ec::PairingEngine;
     use ark_ff::FromBytes;
-    use zokrates_field::ArkFieldExtensions;
-    use zokrates_proof_systems::{G1Affine, G2Affine};
+    use zokrates_field::{ArkFieldExtensions, Field};
+    use zokrates_proof_systems::{BackendError, G1Affine, G2Affine};
 
     #[inline]
-    fn decode_hex(value: String) -> Vec<u8> {
-        let mut bytes = hex::decode(value.strip_prefix("0x").unwrap()).unwrap();
+    fn decode_hex(value: String) -> Result<Vec<u8>, BackendError> {
+        let mut bytes = value
+            .strip_prefix("0x")
+            .ok_or_else(|| BackendError::Encoding(format!("expected a 0x prefix in `{}`", value)))
+            .and_then(|v| {
+                hex::decode(v).map_err(|e| BackendError::Encoding(format!("`{}`: {}", value, e)))
+            })?;
         bytes.reverse();
-        bytes
+        Ok(bytes)
     }
 
-    pub fn to_g1<T: ArkFieldExtensions>(g1: G1Affine) -> <T::ArkEngine as PairingEngine>::G1Affine {
+    pub fn to_g1<T: ArkFieldExtensions>(
+        g1: G1Affine,
+    ) -> Result<<T::ArkEngine as PairingEngine>::G1Affine, BackendError> {
         let mut bytes = vec![];
-        bytes.append(&mut decode_hex(g1.0));
-        bytes.append(&mut decode_hex(g1.1));
+        bytes.append(&mut decode_hex(g1.0)?);
+        bytes.append(&mut decode_hex(g1.1)?);
         bytes.push(0u8); // infinity flag
 
-        <T::ArkEngine as PairingEngine>::G1Affine::read(&*bytes).unwrap()
+        <T::ArkEngine as PairingEngine>::G1Affine::read(&*bytes)
+            .map_err(|e| BackendError::Encoding(e.to_string()))
     }
 
-    pub fn to_g2<T: ArkFieldExtensions>(g2: G2Affine) -> <T::ArkEngine as PairingEngine>::G2Affine {
+    pub fn to_g2<T: ArkFieldExtensions>(
+        g2: G2Affine,
+    ) -> Result<<T::ArkEngine as PairingEngine>::G2Affine, BackendError> {
         let mut bytes = vec![];
 
         match g2 {
             G2Affine::Fq(g2) => {
-                bytes.append(&mut decode_hex(g2.0));
-                bytes.append(&mut decode_hex(g2.1));
+                bytes.append(&mut decode_hex(g2.0)?);
+                bytes.append(&mut decode_hex(g2.1)?);
                 bytes.push(0u8); // infinity flag
             }
             G2Affine::Fq2(g2) => {
-                bytes.append(&mut decode_hex((g2.0).0));
-                bytes.append(&mut decode_hex((g2.0).1));
-                bytes.append(&mut decode_hex((g2.1).0));
-                bytes.append(&mut decode_hex((g2.1).1));
+                bytes.append(&mut decode_hex((g2.0).0)?);
+                bytes.append(&mut decode_hex((g2.0).1)?);
+                bytes.append(&mut decode_hex((g2.1).0)?);
+                bytes.append(&mut decode_hex((g2.1).1)?);
                 bytes.push(0u8); // infinity flag
             }
         };
 
-        <T::ArkEngine as PairingEngine>::G2Affine::read(&*bytes).unwrap()
+        <T::ArkEngine as PairingEngine>::G2Affine::read(&*bytes)
+            .map_err(|e| BackendError::Encoding(e.to_string()))
+    }
+
+    pub fn to_fr<T: Field + ArkFieldExtensions>(
+        fr: &str,
+    ) -> Result<<T::ArkEngine as PairingEngine>::Fr, BackendError> {
+        T::try_from_str(fr.trim_start_matches("0x"), 16)
+            .map(|e| e.into_ark())
+            .map_err(|_| BackendError::Encoding(format!("`{}` is not a field element", fr)))
     }
 }
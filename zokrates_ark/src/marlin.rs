@@ -29,7 +29,7 @@ use crate::{parse_fr, parse_g1, parse_g2, serialization};
 use zokrates_ast::ir::{ProgIterator, Statement, Witness};
 use zokrates_proof_systems::marlin::{self, KZGVerifierKey, ProofPoints, VerificationKey};
 use zokrates_proof_systems::Scheme;
-use zokrates_proof_systems::{Backend, Proof, SetupKeypair, UniversalBackend};
+use zokrates_proof_systems::{Backend, BackendError, Proof, SetupKeypair, UniversalBackend};
 
 const MINIMUM_CONSTRAINT_COUNT: usize = 2;
 
@@ -206,12 +206,12 @@ impl<T: Field + ArkFieldExtensions> UniversalBackend<T, marlin::Marlin> for Ark
 }
 
 impl<T: Field + ArkFieldExtensions> Backend<T, marlin::Marlin> for Ark {
-    fn generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
+    fn try_generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
         program: ProgIterator<'a, T, I>,
         witness: Witness<T>,
         proving_key: Vec<u8>,
         rng: &mut R,
-    ) -> Proof<T, marlin::Marlin> {
+    ) -> Result<Proof<T, marlin::Marlin>, BackendError> {
         let computation = Computation::with_witness(program, witness);
 
         let pk = IndexProverKey::<
@@ -221,16 +221,17 @@ impl<T: Field + ArkFieldExtensions> Backend<T, marlin::Marlin> for Ark {
                 DensePolynomial<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr>,
             >,
         >::deserialize_unchecked(&mut proving_key.as_slice())
-        .unwrap();
+        .map_err(|e| BackendError::ProvingKey(e.to_string()))?;
 
         let public_inputs = computation.public_inputs_values();
         let inputs = public_inputs.iter().map(parse_fr::<T>).collect::<Vec<_>>();
 
-        let proof = MarlinInst::<T>::prove(&pk, computation, rng).unwrap();
+        let proof = MarlinInst::<T>::prove(&pk, computation, rng)
+            .map_err(|e| BackendError::Backend(format!("{:?}", e)))?;
 
         assert!(proof.pc_proof.evals.is_none());
 
-        Proof::new(
+        Ok(Proof::new(
             ProofPoints {
                 commitments: proof
                     .commitments
@@ -258,22 +259,18 @@ impl<T: Field + ArkFieldExtensions> Backend<T, marlin::Marlin> for Ark {
                 prover_messages_count: proof.prover_messages.len(),
             },
             inputs,
-        )
+        ))
     }
 
-    fn verify(
+    fn try_verify(
         vk: <marlin::Marlin as Scheme<T>>::VerificationKey,
         proof: Proof<T, marlin::Marlin>,
-    ) -> bool {
+    ) -> Result<bool, BackendError> {
         let inputs: Vec<_> = proof
             .inputs
             .iter()
-            .map(|s| {
-                T::try_from_str(s.trim_start_matches("0x"), 16)
-                    .unwrap()
-                    .into_ark()
-            })
-            .collect::<Vec<_>>();
+            .map(|s| serialization::to_fr::<T>(s))
+            .collect::<Result<_, _>>()?;
 
         let proof = ArkProof::<
             <<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr,
@@ -288,41 +285,37 @@ impl<T: Field + ArkFieldExtensions> Backend<T, marlin::Marlin> for Ark {
                 .iter()
                 .map(|r| {
                     r.iter()
-                        .map(|(c, shifted_comm)| Commitment {
-                            comm: KZG10Commitment(serialization::to_g1::<T>(c.clone())),
-                            shifted_comm: shifted_comm.clone().map(|shifted_comm| {
-                                KZG10Commitment(serialization::to_g1::<T>(shifted_comm))
-                            }),
+                        .map(|(c, shifted_comm)| {
+                            Ok(Commitment {
+                                comm: KZG10Commitment(serialization::to_g1::<T>(c.clone())?),
+                                shifted_comm: shifted_comm
+                                    .clone()
+                                    .map(|shifted_comm| {
+                                        serialization::to_g1::<T>(shifted_comm).map(KZG10Commitment)
+                                    })
+                                    .transpose()?,
+                            })
                         })
-                        .collect()
+                        .collect::<Result<_, BackendError>>()
                 })
-                .collect(),
+                .collect::<Result<_, _>>()?,
             evaluations: proof
                 .proof
                 .evaluations
-                .into_iter()
-                .map(|v| {
-                    T::try_from_str(v.trim_start_matches("0x"), 16)
-                        .unwrap()
-                        .into_ark()
-                })
-                .collect(),
+                .iter()
+                .map(|v| serialization::to_fr::<T>(v))
+                .collect::<Result<_, _>>()?,
             prover_messages: vec![ProverMsg::EmptyMessage; proof.proof.prover_messages_count],
             pc_proof: BatchLCProof {
                 proof: vec![
                     KZG10Proof {
-                        w: serialization::to_g1::<T>(proof.proof.pc_lc_opening_1),
-                        random_v: Some(
-                            T::try_from_str(
-                                proof.proof.pc_lc_opening_1_degree.trim_start_matches("0x"),
-                                16,
-                            )
-                            .unwrap()
-                            .into_ark(),
-                        ),
+                        w: serialization::to_g1::<T>(proof.proof.pc_lc_opening_1)?,
+                        random_v: Some(serialization::to_fr::<T>(
+                            &proof.proof.pc_lc_opening_1_degree,
+                        )?),
                     },
                     KZG10Proof {
-                        w: serialization::to_g1::<T>(proof.proof.pc_lc_opening_2),
+                        w: serialization::to_g1::<T>(proof.proof.pc_lc_opening_2)?,
                         random_v: None,
                     },
                 ],
@@ -346,35 +339,43 @@ impl<T: Field + ArkFieldExtensions> Backend<T, marlin::Marlin> for Ark {
             index_comms: vk
                 .index_comms
                 .into_iter()
-                .map(|(c, shifted_comm)| Commitment {
-                    comm: KZG10Commitment(serialization::to_g1::<T>(c)),
-                    shifted_comm: shifted_comm.map(|shifted_comm| {
-                        KZG10Commitment(serialization::to_g1::<T>(shifted_comm))
-                    }),
+                .map(|(c, shifted_comm)| {
+                    Ok(Commitment {
+                        comm: KZG10Commitment(serialization::to_g1::<T>(c)?),
+                        shifted_comm: shifted_comm
+                            .map(|shifted_comm| {
+                                serialization::to_g1::<T>(shifted_comm).map(KZG10Commitment)
+                            })
+                            .transpose()?,
+                    })
                 })
-                .collect(),
+                .collect::<Result<_, BackendError>>()?,
             verifier_key: VerifierKey {
-                degree_bounds_and_shift_powers: vk.degree_bounds_and_shift_powers.map(|vk| {
-                    vk.into_iter()
-                        .map(|(bound, pow)| (bound, serialization::to_g1::<T>(pow)))
-                        .collect()
-                }),
+                degree_bounds_and_shift_powers: vk
+                    .degree_bounds_and_shift_powers
+                    .map(|vk| {
+                        vk.into_iter()
+                            .map(|(bound, pow)| Ok((bound, serialization::to_g1::<T>(pow)?)))
+                            .collect::<Result<_, BackendError>>()
+                    })
+                    .transpose()?,
                 max_degree: vk.max_degree,
                 supported_degree: vk.supported_degree,
                 vk: KZG10VerifierKey {
-                    g: serialization::to_g1::<T>(vk.vk.g),
-                    gamma_g: serialization::to_g1::<T>(vk.vk.gamma_g),
-                    h: serialization::to_g2::<T>(vk.vk.h.clone()),
-                    beta_h: serialization::to_g2::<T>(vk.vk.beta_h.clone()),
-                    prepared_h: serialization::to_g2::<T>(vk.vk.h).into(),
-                    prepared_beta_h: serialization::to_g2::<T>(vk.vk.beta_h).into(),
+                    g: serialization::to_g1::<T>(vk.vk.g)?,
+                    gamma_g: serialization::to_g1::<T>(vk.vk.gamma_g)?,
+                    h: serialization::to_g2::<T>(vk.vk.h.clone())?,
+                    beta_h: serialization::to_g2::<T>(vk.vk.beta_h.clone())?,
+                    prepared_h: serialization::to_g2::<T>(vk.vk.h)?.into(),
+                    prepared_beta_h: serialization::to_g2::<T>(vk.vk.beta_h)?.into(),
                 },
             },
         };
 
         let rng = &mut rand_0_8::rngs::StdRng::from_entropy();
 
-        MarlinInst::<T>::verify(&vk, &inputs, &proof, rng).unwrap()
+        MarlinInst::<T>::verify(&vk, &inputs, &proof, rng)
+            .map_err(|e| BackendError::Backend(format!("{:?}", e)))
     }
 }
 
@@ -7,6 +7,7 @@ pub struct SourceMetadata {
     pub file: String,
     pub position: Position,
     pub message: Option<String>,
+    pub code: Option<String>,
 }
 
 impl SourceMetadata {
@@ -15,17 +16,38 @@ impl SourceMetadata {
             file,
             position,
             message: None,
+            code: None,
         }
     }
     pub fn message(mut self, message: Option<String>) -> Self {
         self.message = message;
         self
     }
+    pub fn code(mut self, code: Option<String>) -> Self {
+        self.code = code;
+        self
+    }
+    /// Set the message of a user assertion, reading an error code from a leading `[code]`, as in `"[E01] too low"`
+    pub fn assertion_message(self, message: Option<String>) -> Self {
+        let split = message
+            .as_deref()
+            .and_then(|m| m.strip_prefix('[')?.split_once(']'))
+            .filter(|(code, _)| !code.is_empty())
+            .map(|(code, m)| (code.to_string(), m.trim_start().to_string()));
+
+        match split {
+            Some((code, message)) => self.code(Some(code)).message(Some(message)),
+            None => self.message(message),
+        }
+    }
 }
 
 impl fmt::Display for SourceMetadata {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}:{}", self.file, self.position)?;
+        if let Some(c) = &self.code {
+            write!(f, " [{}]", c)?;
+        }
         match &self.message {
             Some(m) => write!(f, ": \"{}\"", m),
             None => write!(f, ""),
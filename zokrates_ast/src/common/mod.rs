@@ -11,5 +11,5 @@ pub use self::error::RuntimeError;
 pub use self::metadata::SourceMetadata;
 pub use self::parameter::Parameter;
 pub use self::solvers::Solver;
-pub use self::variable::Variable;
+pub use self::variable::{Variable, VariableMap};
 pub use format_string::FormatString;
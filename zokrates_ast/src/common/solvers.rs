@@ -1,4 +1,4 @@
-use crate::zir::ZirFunction;
+use crate::zir::{FieldElementExpression, ZirExpression, ZirFunction, ZirStatement};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -61,6 +61,23 @@ impl<'ast, T> Solver<'ast, T> {
 }
 
 impl<'ast, T> Solver<'ast, T> {
+    /// Returns true if this solver returns its single input unchanged, which is the case for a
+    /// `Zir` solver whose body only returns its field argument.
+    /// Boolean and uint identities are excluded, as solving them checks the type of their input
+    pub fn is_identity(&self) -> bool {
+        match self {
+            Solver::Zir(f) => match (&f.arguments[..], &f.statements[..]) {
+                ([argument], [ZirStatement::Return(expressions)]) => matches!(
+                    &expressions[..],
+                    [ZirExpression::FieldElement(FieldElementExpression::Identifier(e))]
+                        if e.id == argument.id.id
+                ),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     pub fn bits(width: usize) -> Self {
         Solver::Bits(width)
     }
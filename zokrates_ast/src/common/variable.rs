@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::hash::{BuildHasherDefault, Hasher};
 
 // A variable in a constraint system
 // id > 0 for intermediate variables
@@ -88,9 +89,42 @@ impl Variable {
     }
 }
 
+/// A `Hasher` specialised for `Variable`, whose hash only depends on an integer id.
+/// The id is mixed with a multiplicative hash, which is much cheaper than the default `SipHash`
+#[derive(Default)]
+pub struct VariableHasher {
+    hash: u64,
+}
+
+impl VariableHasher {
+    fn add(&mut self, i: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ i).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Hasher for VariableHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.add(*b as u64);
+        }
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.add(i as u64);
+    }
+}
+
+/// A `HashMap` keyed by `Variable`, using `VariableHasher`
+pub type VariableMap<V> = HashMap<Variable, V, BuildHasherDefault<VariableHasher>>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Instant;
 
     #[test]
     fn one() {
@@ -108,4 +142,63 @@ mod tests {
         assert_eq!(format!("{}", Variable::new(0)), "_0");
         assert_eq!(format!("{}", Variable::new(42)), "_42");
     }
+
+    #[test]
+    fn variable_map() {
+        let variables: Vec<_> = (0..1000)
+            .map(Variable::new)
+            .chain((0..1000).map(Variable::public))
+            .chain(std::iter::once(Variable::one()))
+            .collect();
+
+        let mut map: VariableMap<_> = variables.iter().map(|v| (*v, v.id)).collect();
+
+        // private and public variables with the same index are distinct keys
+        assert_eq!(map.len(), variables.len());
+        assert!(variables.iter().all(|v| map[v] == v.id));
+        assert_eq!(map.get(&Variable::new(1000)), None);
+        assert_eq!(map.get(&Variable::public(1000)), None);
+
+        assert_eq!(map.insert(Variable::new(42), 0), Some(42));
+        assert_eq!(map.insert(Variable::new(1000), 1000), None);
+        assert_eq!(
+            map.remove(&Variable::public(42)),
+            Some(Variable::public(42).id)
+        );
+
+        assert_eq!(map.len(), variables.len());
+        assert_eq!(map[&Variable::new(42)], 0);
+        assert_eq!(map[&Variable::new(1000)], 1000);
+        assert!(!map.contains_key(&Variable::public(42)));
+    }
+
+    // run with `cargo test --release -- --ignored variable_map_lookups --nocapture`
+    #[test]
+    #[ignore]
+    fn variable_map_lookups() {
+        let variables: Vec<_> = (0..2_000_000)
+            .map(Variable::new)
+            .chain((0..1000).map(Variable::public))
+            .collect();
+
+        let start = Instant::now();
+        let default: HashMap<_, _> = variables.iter().map(|v| (*v, v.id)).collect();
+        assert!(variables.iter().all(|v| default[v] == v.id));
+        let default_time = start.elapsed();
+
+        let start = Instant::now();
+        let fast: VariableMap<_> = variables.iter().map(|v| (*v, v.id)).collect();
+        assert!(variables.iter().all(|v| fast[v] == v.id));
+        let fast_time = start.elapsed();
+
+        // timings are only reported, as asserting on them would make the test flaky
+        println!(
+            "{} variables: {:?} with the default hasher, {:?} with VariableHasher",
+            variables.len(),
+            default_time,
+            fast_time
+        );
+
+        assert_eq!(fast.len(), default.len());
+    }
 }
@@ -95,6 +95,9 @@ pub fn fold_expression<'ast, T: Field, F: Folder<'ast, T>>(
         FlatExpression::Mult(box left, box right) => {
             FlatExpression::Mult(box f.fold_expression(left), box f.fold_expression(right))
         }
+        FlatExpression::Pow(box base, exponent) => {
+            FlatExpression::Pow(box f.fold_expression(base), exponent)
+        }
     }
 }
 
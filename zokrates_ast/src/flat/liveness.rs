@@ -0,0 +1,118 @@
+// Backward liveness analysis over flat statements
+
+use crate::flat::{FlatStatement, Variable};
+use std::collections::HashSet;
+use zokrates_field::Field;
+
+/// Computes, for each statement, the set of variables which are live right before it is executed,
+/// that is, the variables which are read by this statement or a later one without being redefined in between.
+///
+/// # Arguments
+///
+/// * `statements` - The statements to analyse, in execution order.
+pub fn liveness<T: Field>(statements: &[FlatStatement<T>]) -> Vec<HashSet<Variable>> {
+    let mut live = HashSet::new();
+
+    let mut res: Vec<_> = statements
+        .iter()
+        .rev()
+        .map(|s| {
+            step(s, &mut live);
+            live.clone()
+        })
+        .collect();
+
+    res.reverse();
+    res
+}
+
+// update `live` from the variables live after `s` to the variables live before `s`
+fn step<T: Field>(s: &FlatStatement<T>, live: &mut HashSet<Variable>) {
+    match s {
+        FlatStatement::Block(statements) => {
+            for s in statements.iter().rev() {
+                step(s, live);
+            }
+        }
+        FlatStatement::Definition(v, e) => {
+            live.remove(v);
+            live.extend(e.referenced_variables());
+        }
+        FlatStatement::Condition(lhs, rhs, _) => {
+            live.extend(lhs.referenced_variables());
+            live.extend(rhs.referenced_variables());
+        }
+        FlatStatement::Directive(d) => {
+            for o in &d.outputs {
+                live.remove(o);
+            }
+            for i in &d.inputs {
+                live.extend(i.referenced_variables());
+            }
+        }
+        FlatStatement::Log(_, expressions) => {
+            for (_, expressions) in expressions {
+                for e in expressions {
+                    live.extend(e.referenced_variables());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat::{FlatExpression, RuntimeError};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn dead_after_last_use() {
+        // _1 = _0 * _0
+        // _2 = _1 + _0
+        // _2 == ~out_0
+        let statements: Vec<FlatStatement<Bn128Field>> = vec![
+            FlatStatement::Definition(
+                Variable::new(1),
+                FlatExpression::Mult(
+                    box FlatExpression::Identifier(Variable::new(0)),
+                    box FlatExpression::Identifier(Variable::new(0)),
+                ),
+            ),
+            FlatStatement::Definition(
+                Variable::new(2),
+                FlatExpression::Add(
+                    box FlatExpression::Identifier(Variable::new(1)),
+                    box FlatExpression::Identifier(Variable::new(0)),
+                ),
+            ),
+            FlatStatement::Condition(
+                FlatExpression::Identifier(Variable::new(2)),
+                FlatExpression::Identifier(Variable::public(0)),
+                RuntimeError::Inverse,
+            ),
+        ];
+
+        let live = liveness(&statements);
+
+        assert_eq!(
+            live[0],
+            vec![Variable::new(0), Variable::public(0)]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(
+            live[1],
+            vec![Variable::new(0), Variable::new(1), Variable::public(0)]
+                .into_iter()
+                .collect()
+        );
+        // `_1` and `_0` are dead after their last use
+        assert_eq!(
+            live[2],
+            vec![Variable::new(2), Variable::public(0)]
+                .into_iter()
+                .collect()
+        );
+    }
+}
@@ -6,13 +6,15 @@
 //! @date 2017
 
 pub mod folder;
+pub mod liveness;
 pub mod utils;
 
-use crate::common::FormatString;
 pub use crate::common::Parameter;
 pub use crate::common::RuntimeError;
 pub use crate::common::Variable;
+use crate::common::{FormatString, SourceMetadata};
 
+pub use liveness::liveness;
 pub use utils::{
     flat_expression_from_bits, flat_expression_from_expression_summands,
     flat_expression_from_variable_summands,
@@ -20,9 +22,9 @@ pub use utils::{
 
 use crate::common::Solver;
 use crate::typed::ConcreteType;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use zokrates_field::Field;
+use zokrates_field::{Field, Pow};
 
 pub type FlatProg<'ast, T> = FlatFunction<'ast, T>;
 
@@ -50,6 +52,88 @@ impl<'ast, T, I: IntoIterator<Item = FlatStatement<'ast, T>>> FlatFunctionIterat
     }
 }
 
+impl<'ast, T> FlatFunction<'ast, T> {
+    /// Returns the source locations of the conditions of this function which come from source assertions
+    /// or assembly constraints, in program order
+    pub fn collect_source_metadata(&self) -> Vec<SourceMetadata> {
+        fn collect<T>(statements: &[FlatStatement<T>], metadata: &mut Vec<SourceMetadata>) {
+            for s in statements {
+                match s {
+                    FlatStatement::Block(statements) => collect(statements, metadata),
+                    FlatStatement::Condition(
+                        _,
+                        _,
+                        RuntimeError::SourceAssertion(m)
+                        | RuntimeError::SourceAssemblyConstraint(m),
+                    ) => metadata.push(m.clone()),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut metadata = vec![];
+        collect(&self.statements, &mut metadata);
+        metadata
+    }
+
+    /// Returns true if this function contains no directives, so that its witness is fully determined by its inputs
+    /// through its definitions
+    pub fn is_deterministic(&self) -> bool {
+        fn deterministic<T>(statements: &[FlatStatement<T>]) -> bool {
+            statements.iter().all(|s| match s {
+                FlatStatement::Block(statements) => deterministic(statements),
+                FlatStatement::Directive(_) => false,
+                _ => true,
+            })
+        }
+
+        deterministic(&self.statements)
+    }
+}
+
+/// Returns the number of bits of the largest constant in `program`, which is the smallest field size able to
+/// represent all its constants without reduction. Constants are taken as their least non-negative residue, so
+/// that negative constants require the full field size.
+pub fn required_field_bits<T: Field>(program: &FlatProg<T>) -> usize {
+    fn expression_bits<T: Field>(e: &FlatExpression<T>) -> usize {
+        match e {
+            FlatExpression::Number(n) => n.bits() as usize,
+            FlatExpression::Identifier(_) => 0,
+            FlatExpression::Add(e1, e2)
+            | FlatExpression::Sub(e1, e2)
+            | FlatExpression::Mult(e1, e2) => {
+                std::cmp::max(expression_bits(e1), expression_bits(e2))
+            }
+            FlatExpression::Pow(e, _) => expression_bits(e),
+        }
+    }
+
+    fn statements_bits<T: Field>(statements: &[FlatStatement<T>]) -> usize {
+        statements
+            .iter()
+            .map(|s| match s {
+                FlatStatement::Block(statements) => statements_bits(statements),
+                FlatStatement::Condition(lhs, rhs, _) => {
+                    std::cmp::max(expression_bits(lhs), expression_bits(rhs))
+                }
+                FlatStatement::Definition(_, e) => expression_bits(e),
+                FlatStatement::Directive(d) => {
+                    d.inputs.iter().map(expression_bits).max().unwrap_or(0)
+                }
+                FlatStatement::Log(_, expressions) => expressions
+                    .iter()
+                    .flat_map(|(_, e)| e)
+                    .map(expression_bits)
+                    .max()
+                    .unwrap_or(0),
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    statements_bits(&program.statements)
+}
+
 impl<'ast, T: Field> fmt::Display for FlatFunction<'ast, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -231,6 +315,9 @@ pub enum FlatExpression<T> {
     Add(Box<FlatExpression<T>>, Box<FlatExpression<T>>),
     Sub(Box<FlatExpression<T>>, Box<FlatExpression<T>>),
     Mult(Box<FlatExpression<T>>, Box<FlatExpression<T>>),
+    /// The base raised to a constant exponent. Build it with `FlatExpression::pow` so that only
+    /// powers which fit in a single constraint reach the IR
+    Pow(Box<FlatExpression<T>>, u32),
 }
 
 impl<T> From<T> for FlatExpression<T> {
@@ -240,6 +327,16 @@ impl<T> From<T> for FlatExpression<T> {
 }
 
 impl<T: Field> FlatExpression<T> {
+    /// Raises `base` to `exponent`, returning `None` if the result is not at most quadratic
+    pub fn pow(base: FlatExpression<T>, exponent: u32) -> Option<FlatExpression<T>> {
+        match exponent {
+            0 => Some(FlatExpression::Number(T::one())),
+            1 => Some(base),
+            2 if base.is_linear() => Some(FlatExpression::Pow(box base, 2)),
+            _ => None,
+        }
+    }
+
     pub fn apply_substitution(
         self,
         substitution: &HashMap<Variable, Variable>,
@@ -261,6 +358,60 @@ impl<T: Field> FlatExpression<T> {
                 box e1.apply_substitution(substitution),
                 box e2.apply_substitution(substitution),
             ),
+            FlatExpression::Pow(e, exponent) => {
+                FlatExpression::Pow(box e.apply_substitution(substitution), exponent)
+            }
+        }
+    }
+
+    /// Returns the set of variables this expression reads
+    pub fn referenced_variables(&self) -> HashSet<Variable> {
+        match self {
+            FlatExpression::Number(_) => HashSet::new(),
+            FlatExpression::Identifier(v) => vec![*v].into_iter().collect(),
+            FlatExpression::Add(e1, e2)
+            | FlatExpression::Sub(e1, e2)
+            | FlatExpression::Mult(e1, e2) => {
+                let mut variables = e1.referenced_variables();
+                variables.extend(e2.referenced_variables());
+                variables
+            }
+            FlatExpression::Pow(e, _) => e.referenced_variables(),
+        }
+    }
+
+    /// Evaluates the subtrees of this expression whose leaves are all numbers, leaving the others untouched
+    pub fn fold_constants(self) -> FlatExpression<T> {
+        match self {
+            FlatExpression::Add(box e1, box e2) => {
+                match (e1.fold_constants(), e2.fold_constants()) {
+                    (FlatExpression::Number(n1), FlatExpression::Number(n2)) => {
+                        FlatExpression::Number(n1 + n2)
+                    }
+                    (e1, e2) => FlatExpression::Add(box e1, box e2),
+                }
+            }
+            FlatExpression::Sub(box e1, box e2) => {
+                match (e1.fold_constants(), e2.fold_constants()) {
+                    (FlatExpression::Number(n1), FlatExpression::Number(n2)) => {
+                        FlatExpression::Number(n1 - n2)
+                    }
+                    (e1, e2) => FlatExpression::Sub(box e1, box e2),
+                }
+            }
+            FlatExpression::Mult(box e1, box e2) => {
+                match (e1.fold_constants(), e2.fold_constants()) {
+                    (FlatExpression::Number(n1), FlatExpression::Number(n2)) => {
+                        FlatExpression::Number(n1 * n2)
+                    }
+                    (e1, e2) => FlatExpression::Mult(box e1, box e2),
+                }
+            }
+            FlatExpression::Pow(box e, exponent) => match e.fold_constants() {
+                FlatExpression::Number(n) => FlatExpression::Number(n.pow(exponent as usize)),
+                e => FlatExpression::Pow(box e, exponent),
+            },
+            e => e,
         }
     }
 
@@ -282,6 +433,9 @@ impl<T: Field> FlatExpression<T> {
                         box FlatExpression::Number(_)
                     )
             ),
+            FlatExpression::Pow(_, 0) => true,
+            FlatExpression::Pow(ref e, 1) => e.is_linear(),
+            FlatExpression::Pow(..) => false,
         }
     }
 }
@@ -294,6 +448,7 @@ impl<T: Field> fmt::Display for FlatExpression<T> {
             FlatExpression::Add(ref lhs, ref rhs) => write!(f, "({} + {})", lhs, rhs),
             FlatExpression::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
             FlatExpression::Mult(ref lhs, ref rhs) => write!(f, "({} * {})", lhs, rhs),
+            FlatExpression::Pow(ref base, exponent) => write!(f, "({} ** {})", base, exponent),
         }
     }
 }
@@ -314,3 +469,202 @@ impl fmt::Display for Error {
         write!(f, "{}", self.message)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::untyped::Position;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn fold_constants() {
+        let n = |v| FlatExpression::Number(Bn128Field::from(v));
+        let x = || FlatExpression::Identifier(Variable::new(0));
+
+        assert_eq!(
+            FlatExpression::Add(box n(2), box n(3)).fold_constants(),
+            n(5)
+        );
+        assert_eq!(
+            FlatExpression::Sub(box n(2), box n(3)).fold_constants(),
+            n(-1)
+        );
+        assert_eq!(
+            FlatExpression::Mult(box n(2), box n(3)).fold_constants(),
+            n(6)
+        );
+
+        // (x + (2 * 3)) * (1 - 1) only has its constant subtrees folded
+        let e = FlatExpression::Mult(
+            box FlatExpression::Add(box x(), box FlatExpression::Mult(box n(2), box n(3))),
+            box FlatExpression::Sub(box n(1), box n(1)),
+        );
+
+        assert_eq!(
+            e.fold_constants(),
+            FlatExpression::Mult(box FlatExpression::Add(box x(), box n(6)), box n(0))
+        );
+    }
+
+    #[test]
+    fn pow() {
+        let x = || box FlatExpression::<Bn128Field>::Identifier(Variable::new(0));
+
+        let e = FlatExpression::Pow(x(), 3);
+        assert_eq!(e.to_string(), "(_0 ** 3)");
+        assert!(!e.is_linear());
+        assert_eq!(
+            e.referenced_variables(),
+            vec![Variable::new(0)].into_iter().collect()
+        );
+        assert_eq!(
+            e.apply_substitution(
+                &vec![(Variable::new(0), Variable::new(1))]
+                    .into_iter()
+                    .collect()
+            ),
+            FlatExpression::Pow(box FlatExpression::Identifier(Variable::new(1)), 3)
+        );
+
+        assert_eq!(
+            FlatExpression::Pow(box FlatExpression::Number(Bn128Field::from(2)), 3)
+                .fold_constants(),
+            FlatExpression::Number(Bn128Field::from(8))
+        );
+
+        assert!(FlatExpression::Pow(x(), 0).is_linear());
+        assert!(FlatExpression::Pow(x(), 1).is_linear());
+        assert!(!FlatExpression::Pow(box FlatExpression::Mult(x(), x()), 1).is_linear());
+    }
+
+    #[test]
+    fn checked_pow() {
+        let x = || FlatExpression::<Bn128Field>::Identifier(Variable::new(0));
+
+        assert_eq!(
+            FlatExpression::pow(x(), 0),
+            Some(FlatExpression::Number(Bn128Field::from(1)))
+        );
+        assert_eq!(FlatExpression::pow(x(), 1), Some(x()));
+        assert_eq!(
+            FlatExpression::pow(x(), 2),
+            Some(FlatExpression::Pow(box x(), 2))
+        );
+        assert_eq!(FlatExpression::pow(x(), 3), None);
+        assert_eq!(
+            FlatExpression::pow(FlatExpression::Mult(box x(), box x()), 2),
+            None
+        );
+    }
+
+    #[test]
+    fn required_field_bits() {
+        let mut f: FlatProg<Bn128Field> = FlatFunction {
+            arguments: vec![],
+            statements: vec![FlatStatement::Definition(
+                Variable::new(1),
+                FlatExpression::Mult(
+                    box FlatExpression::Number(Bn128Field::from(3)),
+                    box FlatExpression::Identifier(Variable::new(0)),
+                ),
+            )],
+            return_count: 0,
+        };
+
+        assert_eq!(super::required_field_bits(&f), 2);
+
+        // 2**200 + 1 needs 201 bits
+        let large = Bn128Field::from(2).pow(200usize) + Bn128Field::from(1);
+
+        f.statements
+            .push(FlatStatement::Block(vec![FlatStatement::Condition(
+                FlatExpression::Identifier(Variable::new(1)),
+                FlatExpression::Add(
+                    box FlatExpression::Identifier(Variable::new(0)),
+                    box FlatExpression::Number(large),
+                ),
+                RuntimeError::Bitness,
+            )]));
+
+        assert_eq!(super::required_field_bits(&f), 201);
+    }
+
+    #[test]
+    fn collect_source_metadata() {
+        let metadata = SourceMetadata::new("main.zok".into(), Position { line: 2, col: 5 });
+
+        let f: FlatFunction<Bn128Field> = FlatFunction {
+            arguments: vec![],
+            statements: vec![
+                FlatStatement::Condition(
+                    FlatExpression::Identifier(Variable::new(0)),
+                    FlatExpression::Number(Bn128Field::from(1)),
+                    RuntimeError::Bitness,
+                ),
+                FlatStatement::Block(vec![FlatStatement::Condition(
+                    FlatExpression::Identifier(Variable::new(0)),
+                    FlatExpression::Number(Bn128Field::from(1)),
+                    RuntimeError::SourceAssertion(metadata.clone()),
+                )]),
+            ],
+            return_count: 0,
+        };
+
+        assert_eq!(f.collect_source_metadata(), vec![metadata]);
+    }
+
+    #[test]
+    fn is_deterministic() {
+        let definition = FlatStatement::Definition(
+            Variable::new(1),
+            FlatExpression::Identifier(Variable::new(0)),
+        );
+
+        let mut f: FlatFunction<Bn128Field> = FlatFunction {
+            arguments: vec![],
+            statements: vec![definition],
+            return_count: 0,
+        };
+
+        assert!(f.is_deterministic());
+
+        f.statements
+            .push(FlatStatement::Block(vec![FlatStatement::Directive(
+                FlatDirective {
+                    inputs: vec![FlatExpression::Identifier(Variable::new(0))],
+                    outputs: vec![Variable::new(2)],
+                    solver: Solver::Bits(8),
+                },
+            )]));
+
+        assert!(!f.is_deterministic());
+    }
+
+    #[test]
+    fn condition_keeps_custom_error() {
+        let error = RuntimeError::SourceAssertion(
+            SourceMetadata::new("main.zok".into(), Position { line: 2, col: 5 })
+                .message(Some("balance too low".into()))
+                .code(Some("E_BALANCE".into())),
+        );
+
+        let statement = FlatStatement::Condition(
+            FlatExpression::Identifier(Variable::new(0)),
+            FlatExpression::Number(Bn128Field::from(1)),
+            error.clone(),
+        );
+
+        let substitution = HashMap::new();
+        let statement = statement.apply_substitution(&substitution);
+
+        match &statement {
+            FlatStatement::Condition(_, _, e) => assert_eq!(e, &error),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            statement.to_string(),
+            "_0 == 1 // Assertion failed at main.zok:2:5 [E_BALANCE]: \"balance too low\""
+        );
+    }
+}
@@ -2,16 +2,31 @@ use crate::flat::{FlatDirective, FlatExpression, FlatProgIterator, FlatStatement
 use crate::ir::{Directive, LinComb, ProgIterator, QuadComb, Statement};
 use zokrates_field::Field;
 
+// strip the trivial powers `e ** 1` and `e ** 0` at the top of an expression
+fn reduce_power<T: Field>(e: FlatExpression<T>) -> FlatExpression<T> {
+    match e {
+        FlatExpression::Pow(_, 0) => FlatExpression::Number(T::one()),
+        FlatExpression::Pow(box e, 1) => reduce_power(e),
+        e => e,
+    }
+}
+
 impl<T: Field> QuadComb<T> {
     fn from_flat_expression<U: Into<FlatExpression<T>>>(flat_expression: U) -> QuadComb<T> {
-        let flat_expression = flat_expression.into();
+        let flat_expression = reduce_power(flat_expression.into());
         match flat_expression.is_linear() {
             true => LinComb::from(flat_expression).into(),
             false => match flat_expression {
                 FlatExpression::Mult(box e1, box e2) => {
                     QuadComb::from_linear_combinations(e1.into(), e2.into())
                 }
-                e => unimplemented!("{}", e),
+                FlatExpression::Pow(box e, 2) => {
+                    QuadComb::from_linear_combinations(e.clone().into(), e.into())
+                }
+                e => unreachable!(
+                    "{} is not quadratic, use FlatExpression::pow to build powers",
+                    e
+                ),
             },
         }
     }
@@ -47,6 +62,8 @@ impl<T: Field> From<FlatExpression<T>> for LinComb<T> {
                 box FlatExpression::Number(n1),
                 box FlatExpression::Number(n2),
             ) => LinComb::summand(n1 * n2, Variable::one()),
+            FlatExpression::Pow(_, 0) => LinComb::summand(T::one(), Variable::one()),
+            FlatExpression::Pow(box e, 1) => LinComb::from(e),
             e => unreachable!("{}", e),
         }
     }
@@ -58,20 +75,30 @@ impl<'ast, T: Field> From<FlatStatement<'ast, T>> for Statement<'ast, T> {
             FlatStatement::Block(statements) => {
                 Statement::Block(statements.into_iter().map(Statement::from).collect())
             }
-            FlatStatement::Condition(linear, quadratic, message) => match quadratic {
+            FlatStatement::Condition(linear, quadratic, message) => match reduce_power(quadratic) {
                 FlatExpression::Mult(box lhs, box rhs) => Statement::Constraint(
                     QuadComb::from_linear_combinations(lhs.into(), rhs.into()),
                     linear.into(),
                     Some(message),
                 ),
+                FlatExpression::Pow(box e, 2) => Statement::Constraint(
+                    QuadComb::from_linear_combinations(e.clone().into(), e.into()),
+                    linear.into(),
+                    Some(message),
+                ),
                 e => Statement::Constraint(LinComb::from(e).into(), linear.into(), Some(message)),
             },
-            FlatStatement::Definition(var, quadratic) => match quadratic {
+            FlatStatement::Definition(var, quadratic) => match reduce_power(quadratic) {
                 FlatExpression::Mult(box lhs, box rhs) => Statement::Constraint(
                     QuadComb::from_linear_combinations(lhs.into(), rhs.into()),
                     var.into(),
                     None,
                 ),
+                FlatExpression::Pow(box e, 2) => Statement::Constraint(
+                    QuadComb::from_linear_combinations(e.clone().into(), e.into()),
+                    var.into(),
+                    None,
+                ),
                 e => Statement::Constraint(LinComb::from(e).into(), var.into(), None),
             },
             FlatStatement::Directive(ds) => Statement::Directive(ds.into()),
@@ -176,4 +203,52 @@ mod tests {
             LinComb::summand(42, Variable::new(42)) + LinComb::summand(21, Variable::new(21));
         assert_eq!(LinComb::from(add), expected);
     }
+
+    #[test]
+    fn square() {
+        // x ** 2 = y
+        let statement: FlatStatement<Bn128Field> = FlatStatement::Definition(
+            Variable::new(1),
+            FlatExpression::Pow(box FlatExpression::Identifier(Variable::new(0)), 2),
+        );
+        let expected = Statement::Constraint(
+            QuadComb::from_linear_combinations(Variable::new(0).into(), Variable::new(0).into()),
+            Variable::new(1).into(),
+            None,
+        );
+        assert_eq!(Statement::from(statement), expected);
+    }
+
+    #[test]
+    fn trivial_powers() {
+        // (x * y) ** 1 = z
+        let statement: FlatStatement<Bn128Field> = FlatStatement::Definition(
+            Variable::new(2),
+            FlatExpression::Pow(
+                box FlatExpression::Mult(
+                    box FlatExpression::Identifier(Variable::new(0)),
+                    box FlatExpression::Identifier(Variable::new(1)),
+                ),
+                1,
+            ),
+        );
+        let expected = Statement::Constraint(
+            QuadComb::from_linear_combinations(Variable::new(0).into(), Variable::new(1).into()),
+            Variable::new(2).into(),
+            None,
+        );
+        assert_eq!(Statement::from(statement), expected);
+
+        // x ** 0 = y
+        let statement: FlatStatement<Bn128Field> = FlatStatement::Definition(
+            Variable::new(1),
+            FlatExpression::Pow(box FlatExpression::Identifier(Variable::new(0)), 0),
+        );
+        let expected = Statement::Constraint(
+            LinComb::summand(1, Variable::one()).into(),
+            Variable::new(1).into(),
+            None,
+        );
+        assert_eq!(Statement::from(statement), expected);
+    }
 }
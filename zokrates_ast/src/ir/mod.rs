@@ -25,7 +25,7 @@ pub use crate::common::RuntimeError;
 pub use crate::common::Solver;
 pub use crate::common::Variable;
 
-pub use self::witness::Witness;
+pub use self::witness::{diff_witnesses, Witness};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Derivative)]
 #[derivative(Hash, PartialEq, Eq)]
@@ -185,6 +185,20 @@ impl<'ast, T> Prog<'ast, T> {
             .count()
     }
 
+    /// Returns true if this program contains no directives, so that it has no witness values other than
+    /// its inputs and the ones they determine through constraints
+    pub fn is_deterministic(&self) -> bool {
+        fn deterministic<T>(statements: &[Statement<T>]) -> bool {
+            statements.iter().all(|s| match s {
+                Statement::Block(statements) => deterministic(statements),
+                Statement::Directive(_) => false,
+                _ => true,
+            })
+        }
+
+        deterministic(&self.statements)
+    }
+
     pub fn into_prog_iter(
         self,
     ) -> ProgIterator<'ast, T, impl IntoIterator<Item = Statement<'ast, T>>> {
@@ -244,4 +258,23 @@ mod tests {
             assert_eq!(format!("{}", c), "(1 * _42) * (1 * _42) == 1 * _42")
         }
     }
+
+    #[test]
+    fn is_deterministic() {
+        let mut p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![Statement::constraint(Variable::new(0), Variable::public(0))],
+            return_count: 1,
+        };
+
+        assert!(p.is_deterministic());
+
+        p.statements.push(Statement::Directive(Directive {
+            inputs: vec![LinComb::from(Variable::new(0)).into()],
+            outputs: vec![Variable::new(1)],
+            solver: Solver::Bits(8),
+        }));
+
+        assert!(!p.is_deterministic());
+    }
 }
@@ -22,6 +22,14 @@ impl<T: Field> Witness<T> {
             .collect()
     }
 
+    pub fn from_map(map: HashMap<Variable, T>) -> Self {
+        Witness(map.into_iter().collect())
+    }
+
+    pub fn get(&self, var: &Variable) -> Option<&T> {
+        self.0.get(var)
+    }
+
     pub fn insert(&mut self, var: Variable, val: T) -> Option<T> {
         self.0.insert(var, val)
     }
@@ -92,6 +100,19 @@ impl<T: Field> Witness<T> {
     }
 }
 
+/// Compares two witnesses of the same program, returning the variables assigned to different values
+/// along with their value in `a` and `b`, ordered by variable. Variables missing from either witness are ignored.
+pub fn diff_witnesses<T: Field>(a: &Witness<T>, b: &Witness<T>) -> Vec<(Variable, T, T)> {
+    a.0.iter()
+        .filter_map(|(variable, value_a)| match b.get(variable) {
+            Some(value_b) if value_a != value_b => {
+                Some((*variable, value_a.clone(), value_b.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 impl<T: Field> fmt::Display for Witness<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -111,6 +132,44 @@ mod tests {
     use super::*;
     use zokrates_field::Bn128Field;
 
+    #[test]
+    fn from_map() {
+        let w = Witness::from_map(
+            vec![
+                (Variable::one(), Bn128Field::from(1)),
+                (Variable::new(0), Bn128Field::from(42)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(w.get(&Variable::one()), Some(&Bn128Field::from(1)));
+        assert_eq!(w.get(&Variable::new(0)), Some(&Bn128Field::from(42)));
+        assert_eq!(w.get(&Variable::public(0)), None);
+    }
+
+    #[test]
+    fn diff() {
+        let a = Witness(
+            vec![
+                (Variable::one(), Bn128Field::from(1)),
+                (Variable::new(0), Bn128Field::from(42)),
+                (Variable::public(0), Bn128Field::from(43)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let mut b = a.clone();
+        b.insert(Variable::new(0), Bn128Field::from(41));
+
+        assert_eq!(diff_witnesses(&a, &a), vec![]);
+        assert_eq!(
+            diff_witnesses(&a, &b),
+            vec![(Variable::new(0), Bn128Field::from(42), Bn128Field::from(41))]
+        );
+    }
+
     mod io {
         use super::*;
         use std::io::Cursor;
@@ -32,7 +32,7 @@ mod tests {
     use super::*;
     use crate::typed::types::{
         ConcreteArrayType, ConcreteFunctionKey, ConcreteStructMember, ConcreteStructType,
-        GTupleType, UBitwidth,
+        GTupleType, StructLocation, UBitwidth,
     };
     use crate::typed::DeclarationType;
     use crate::typed::{
@@ -306,6 +306,42 @@ mod tests {
         assert_eq!(de_abi, abi);
     }
 
+    #[test]
+    fn serialize_struct_location() {
+        let mut foo = ConcreteStructType::new(
+            "/home/user/lib/foo.zok".into(),
+            "Foo".into(),
+            vec![],
+            vec![ConcreteStructMember::new(
+                String::from("a"),
+                ConcreteType::FieldElement,
+            )],
+        );
+        foo.location = Some(StructLocation {
+            module: "/home/user/main.zok".into(),
+            name: "Bar".into(),
+        });
+
+        let abi: Abi = Abi {
+            inputs: vec![AbiInput {
+                name: String::from("foo"),
+                public: true,
+                ty: ConcreteType::Struct(foo.clone()),
+            }],
+            output: ConcreteType::Struct(foo),
+        };
+
+        // the source locations of structs are not part of the ABI
+        let json = serde_json::to_string(&abi).unwrap();
+        assert_eq!(
+            &json,
+            r#"{"inputs":[{"name":"foo","public":true,"type":"struct","components":{"name":"Foo","generics":[],"members":[{"name":"a","type":"field"}]}}],"output":{"type":"struct","components":{"name":"Foo","generics":[],"members":[{"name":"a","type":"field"}]}}}"#
+        );
+
+        let de_abi: Abi = serde_json::from_str(json.as_ref()).unwrap();
+        assert_eq!(serde_json::to_string(&de_abi).unwrap(), json);
+    }
+
     #[test]
     fn serialize_nested_struct() {
         let abi: Abi = Abi {
@@ -0,0 +1,89 @@
+// Count the nonlinear operations of a typed program, as an approximation of its constraint count
+
+use crate::typed::folder::*;
+use crate::typed::*;
+use zokrates_field::Field;
+
+#[derive(Default)]
+struct MultiplicativeComplexity {
+    count: usize,
+}
+
+impl<'ast, T: Field> TypedProgram<'ast, T> {
+    /// Returns the number of multiplication-like operations (field `Mult` and `Pow`, uint `Mult`) in this program
+    pub fn multiplicative_complexity(&self) -> usize {
+        let mut counter = MultiplicativeComplexity::default();
+        counter.fold_program(self.clone());
+        counter.count
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for MultiplicativeComplexity {
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        if matches!(
+            e,
+            FieldElementExpression::Mult(..) | FieldElementExpression::Pow(..)
+        ) {
+            self.count += 1;
+        }
+        fold_field_expression(self, e)
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        if matches!(e, UExpressionInner::Mult(..)) {
+            self.count += 1;
+        }
+        fold_uint_expression_inner(self, bitwidth, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn count_mult_and_pow() {
+        let a = || FieldElementExpression::<Bn128Field>::identifier("a".into());
+
+        // a * a + a * a ** 2
+        let main: TypedFunction<Bn128Field> = TypedFunction {
+            arguments: vec![DeclarationVariable::field_element("a").into()],
+            statements: vec![TypedStatement::Return(
+                (a() * a() + a() * FieldElementExpression::pow(a(), 2u32.into())).into(),
+            )],
+            signature: DeclarationSignature::new()
+                .inputs(vec![DeclarationType::FieldElement])
+                .output(DeclarationType::FieldElement),
+        };
+
+        let p = TypedProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                TypedModule {
+                    symbols: vec![TypedFunctionSymbolDeclaration::new(
+                        DeclarationFunctionKey::with_location("main", "main").signature(
+                            DeclarationSignature::new()
+                                .inputs(vec![DeclarationType::FieldElement])
+                                .output(DeclarationType::FieldElement),
+                        ),
+                        TypedFunctionSymbol::Here(main),
+                    )
+                    .into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        assert_eq!(p.multiplicative_complexity(), 3);
+    }
+}
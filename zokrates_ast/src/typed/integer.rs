@@ -11,6 +11,7 @@ use crate::typed::{
     TypedExpressionOrSpread, TypedSpread, UExpression, UExpressionInner,
 };
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
 use std::ops::{Add, Div, Mul, Neg, Not, Rem, Sub};
@@ -272,9 +273,10 @@ impl<'ast, T: Field> TypedExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum IntExpression<'ast, T> {
     Value(BigUint),
+    #[serde(borrow)]
     Pos(Box<IntExpression<'ast, T>>),
     Neg(Box<IntExpression<'ast, T>>),
     Add(Box<IntExpression<'ast, T>>, Box<IntExpression<'ast, T>>),
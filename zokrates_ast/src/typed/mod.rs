@@ -6,9 +6,11 @@
 //! @date 2017
 
 pub mod abi;
+mod complexity;
 pub mod folder;
 pub mod identifier;
 pub mod result_folder;
+mod source_metadata;
 
 mod integer;
 mod parameter;
@@ -27,7 +29,7 @@ pub use self::types::{
     UBitwidth,
 };
 use self::types::{ConcreteArrayType, ConcreteStructType};
-use crate::typed::types::{ConcreteGenericsAssignment, IntoType};
+use crate::typed::types::{with_struct_locations, ConcreteGenericsAssignment, IntoType};
 
 pub use self::variable::{ConcreteVariable, DeclarationVariable, GVariable, Variable};
 use std::marker::PhantomData;
@@ -37,6 +39,7 @@ pub use crate::typed::integer::IntExpression;
 pub use crate::typed::uint::{bitwidth, UExpression, UExpressionInner, UMetadata};
 
 use crate::common::{FlatEmbed, FormatString, SourceMetadata};
+use serde::{Deserialize, Serialize};
 
 use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
@@ -66,8 +69,9 @@ pub type TypedModules<'ast, T> = BTreeMap<OwnedTypedModuleId, TypedModule<'ast,
 pub type TypedFunctionSymbols<'ast, T> =
     BTreeMap<DeclarationFunctionKey<'ast, T>, TypedFunctionSymbol<'ast, T>>;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TypedConstantSymbol<'ast, T> {
+    #[serde(borrow)]
     Here(TypedConstant<'ast, T>),
     There(CanonicalConstantIdentifier<'ast>),
 }
@@ -80,13 +84,29 @@ pub type TypedConstantSymbols<'ast, T> = Vec<(
 )>;
 
 /// A typed program as a collection of modules, one of them being the main
-#[derive(PartialEq, Eq, Debug, Clone)]
+///
+/// A program can be serialized with serde, for example to JSON for external tooling. The encoding mirrors the
+/// AST: modules are keyed by path, and statements and expressions are externally tagged enums named after their variants.
+/// Types use the same encoding as the ABI, which does not record the module in which a struct is declared: use
+/// `to_json` and `from_json` to keep it.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct TypedProgram<'ast, T> {
+    #[serde(borrow)]
     pub modules: TypedModules<'ast, T>,
     pub main: OwnedTypedModuleId,
 }
 
 impl<'ast, T: Field> TypedProgram<'ast, T> {
+    /// Serialize this program to JSON, including the source locations of structs
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        with_struct_locations(|| serde_json::to_string(self))
+    }
+
+    /// Deserialize a program serialized with `to_json`
+    pub fn from_json(json: &'ast str) -> serde_json::Result<Self> {
+        with_struct_locations(|| serde_json::from_str(json))
+    }
+
     pub fn abi(&self) -> Abi {
         let main = &self.modules[&self.main]
             .functions_iter()
@@ -151,8 +171,9 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedProgram<'ast, T> {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct TypedFunctionSymbolDeclaration<'ast, T> {
+    #[serde(borrow)]
     pub key: DeclarationFunctionKey<'ast, T>,
     pub symbol: TypedFunctionSymbol<'ast, T>,
 }
@@ -163,8 +184,9 @@ impl<'ast, T> TypedFunctionSymbolDeclaration<'ast, T> {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct TypedConstantSymbolDeclaration<'ast, T> {
+    #[serde(borrow)]
     pub id: CanonicalConstantIdentifier<'ast>,
     pub symbol: TypedConstantSymbol<'ast, T>,
 }
@@ -179,8 +201,9 @@ impl<'ast, T> TypedConstantSymbolDeclaration<'ast, T> {
 }
 
 #[allow(clippy::large_enum_variant)]
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub enum TypedSymbolDeclaration<'ast, T> {
+    #[serde(borrow)]
     Function(TypedFunctionSymbolDeclaration<'ast, T>),
     Constant(TypedConstantSymbolDeclaration<'ast, T>),
 }
@@ -209,8 +232,9 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedSymbolDeclaration<'ast, T> {
 pub type TypedSymbolDeclarations<'ast, T> = Vec<TypedSymbolDeclaration<'ast, T>>;
 
 /// A typed module as a collection of functions. Types have been resolved during semantic checking.
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct TypedModule<'ast, T> {
+    #[serde(borrow)]
     pub symbols: TypedSymbolDeclarations<'ast, T>,
 }
 
@@ -232,8 +256,9 @@ impl<'ast, T> TypedModule<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum TypedFunctionSymbol<'ast, T> {
+    #[serde(borrow)]
     Here(TypedFunction<'ast, T>),
     There(DeclarationFunctionKey<'ast, T>),
     Flat(FlatEmbed),
@@ -315,9 +340,10 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedModule<'ast, T> {
 }
 
 /// A typed function
-#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
 pub struct TypedFunction<'ast, T> {
     /// Arguments of the function
+    #[serde(borrow)]
     pub arguments: Vec<DeclarationParameter<'ast, T>>,
     /// Vector of statements that are executed when running the function
     pub statements: Vec<TypedStatement<'ast, T>>,
@@ -374,8 +400,9 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedFunction<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct TypedConstant<'ast, T> {
+    #[serde(borrow)]
     pub expression: TypedExpression<'ast, T>,
     pub ty: DeclarationType<'ast, T>,
 }
@@ -400,16 +427,18 @@ impl<'ast, T: Field> Typed<'ast, T> for TypedConstant<'ast, T> {
 
 /// Something we can assign to.
 #[allow(clippy::large_enum_variant)]
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TypedAssignee<'ast, T> {
+    #[serde(borrow)]
     Identifier(Variable<'ast, T>),
     Select(Box<TypedAssignee<'ast, T>>, Box<UExpression<'ast, T>>),
     Member(Box<TypedAssignee<'ast, T>>, MemberId),
     Element(Box<TypedAssignee<'ast, T>>, u32),
 }
 
-#[derive(Clone, PartialEq, Hash, Eq, Debug, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Hash, Eq, Debug, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TypedSpread<'ast, T> {
+    #[serde(borrow)]
     pub array: ArrayExpression<'ast, T>,
 }
 
@@ -425,8 +454,9 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedSpread<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TypedExpressionOrSpread<'ast, T> {
+    #[serde(borrow)]
     Expression(TypedExpression<'ast, T>),
     Spread(TypedSpread<'ast, T>),
 }
@@ -567,7 +597,7 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedAssignee<'ast, T> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RuntimeError {
     SourceAssertion(SourceMetadata),
     SelectRangeCheck,
@@ -584,10 +614,11 @@ impl fmt::Display for RuntimeError {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct EmbedCall<'ast, T> {
     pub embed: FlatEmbed,
     pub generics: Vec<u32>,
+    #[serde(borrow)]
     pub arguments: Vec<TypedExpression<'ast, T>>,
 }
 
@@ -631,8 +662,9 @@ impl<'ast, T: fmt::Display> fmt::Display for EmbedCall<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DefinitionRhs<'ast, T> {
+    #[serde(borrow)]
     Expression(TypedExpression<'ast, T>),
     EmbedCall(EmbedCall<'ast, T>),
 }
@@ -658,8 +690,9 @@ impl<'ast, T: fmt::Display> fmt::Display for DefinitionRhs<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TypedAssemblyStatement<'ast, T> {
+    #[serde(borrow)]
     Assignment(TypedAssignee<'ast, T>, TypedExpression<'ast, T>),
     Constraint(
         FieldElementExpression<'ast, T>,
@@ -683,8 +716,9 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedAssemblyStatement<'ast, T> {
 
 /// A statement in a `TypedFunction`
 #[allow(clippy::large_enum_variant)]
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TypedStatement<'ast, T> {
+    #[serde(borrow)]
     Return(TypedExpression<'ast, T>),
     Definition(TypedAssignee<'ast, T>, DefinitionRhs<'ast, T>),
     Assertion(BooleanExpression<'ast, T>, RuntimeError),
@@ -798,8 +832,9 @@ pub trait Typed<'ast, T> {
 
 /// A typed expression
 #[allow(clippy::large_enum_variant)]
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TypedExpression<'ast, T> {
+    #[serde(borrow)]
     Boolean(BooleanExpression<'ast, T>),
     FieldElement(FieldElementExpression<'ast, T>),
     Uint(UExpression<'ast, T>),
@@ -955,7 +990,7 @@ impl<'ast, T: Clone> Typed<'ast, T> for BooleanExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct EqExpression<E> {
     pub left: Box<E>,
     pub right: Box<E>,
@@ -976,8 +1011,9 @@ impl<E: fmt::Display> fmt::Display for EqExpression<E> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BlockExpression<'ast, T, E> {
+    #[serde(borrow)]
     pub statements: Vec<TypedStatement<'ast, T>>,
     pub value: Box<E>,
 }
@@ -991,9 +1027,11 @@ impl<'ast, T, E> BlockExpression<'ast, T, E> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct IdentifierExpression<'ast, E> {
+    #[serde(borrow)]
     pub id: Identifier<'ast>,
+    #[serde(skip)]
     ty: PhantomData<E>,
 }
 
@@ -1012,10 +1050,12 @@ impl<'ast, E> fmt::Display for IdentifierExpression<'ast, E> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct MemberExpression<'ast, T, E> {
+    #[serde(borrow)]
     pub struc: Box<StructExpression<'ast, T>>,
     pub id: MemberId,
+    #[serde(skip)]
     ty: PhantomData<E>,
 }
 
@@ -1035,10 +1075,12 @@ impl<'ast, T: fmt::Display, E> fmt::Display for MemberExpression<'ast, T, E> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct SelectExpression<'ast, T, E> {
+    #[serde(borrow)]
     pub array: Box<ArrayExpression<'ast, T>>,
     pub index: Box<UExpression<'ast, T>>,
+    #[serde(skip)]
     ty: PhantomData<E>,
 }
 
@@ -1058,10 +1100,12 @@ impl<'ast, T: fmt::Display, E> fmt::Display for SelectExpression<'ast, T, E> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ElementExpression<'ast, T, E> {
+    #[serde(borrow)]
     pub tuple: Box<TupleExpression<'ast, T>>,
     pub index: u32,
+    #[serde(skip)]
     ty: PhantomData<E>,
 }
 
@@ -1081,14 +1125,19 @@ impl<'ast, T: fmt::Display, E> fmt::Display for ElementExpression<'ast, T, E> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ConditionalKind {
     IfElse,
     Ternary,
+    /// A conditional evaluating to the minimum of two values, such as `a < b ? a : b`
+    Min,
+    /// A conditional evaluating to the maximum of two values, such as `a < b ? b : a`
+    Max,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ConditionalExpression<'ast, T, E> {
+    #[serde(borrow)]
     pub condition: Box<BooleanExpression<'ast, T>>,
     pub consequence: Box<E>,
     pub alternative: Box<E>,
@@ -1121,7 +1170,7 @@ impl<'ast, T: fmt::Display, E: fmt::Display> fmt::Display for ConditionalExpress
                     self.condition, self.consequence, self.alternative
                 )
             }
-            ConditionalKind::Ternary => write!(
+            ConditionalKind::Ternary | ConditionalKind::Min | ConditionalKind::Max => write!(
                 f,
                 "{} ? {} : {}",
                 self.condition, self.consequence, self.alternative
@@ -1130,11 +1179,13 @@ impl<'ast, T: fmt::Display, E: fmt::Display> fmt::Display for ConditionalExpress
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct FunctionCallExpression<'ast, T, E> {
+    #[serde(borrow)]
     pub function_key: DeclarationFunctionKey<'ast, T>,
     pub generics: Vec<Option<UExpression<'ast, T>>>,
     pub arguments: Vec<TypedExpression<'ast, T>>,
+    #[serde(skip)]
     ty: PhantomData<E>,
 }
 
@@ -1183,8 +1234,9 @@ impl<'ast, T: fmt::Display, E> fmt::Display for FunctionCallExpression<'ast, T,
 }
 
 /// An expression of type `field`
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum FieldElementExpression<'ast, T> {
+    #[serde(borrow)]
     Block(BlockExpression<'ast, T, Self>),
     Number(T),
     Identifier(IdentifierExpression<'ast, Self>),
@@ -1348,8 +1400,9 @@ impl<'ast, T> From<T> for FieldElementExpression<'ast, T> {
 }
 
 /// An expression of type `bool`
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum BooleanExpression<'ast, T> {
+    #[serde(borrow)]
     Block(BlockExpression<'ast, T, Self>),
     Identifier(IdentifierExpression<'ast, Self>),
     Value(bool),
@@ -1406,14 +1459,15 @@ impl<'ast, T> From<bool> for BooleanExpression<'ast, T> {
 /// * Contrary to basic types which are represented as enums, we wrap an enum `ArrayExpressionInner` in a struct in order to keep track of the type (content and size)
 /// of the array. Only using an enum would require generics, which would propagate up to TypedExpression which we want to keep simple, hence this "runtime"
 /// type checking
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ArrayExpression<'ast, T> {
+    #[serde(borrow)]
     pub ty: Box<ArrayType<'ast, T>>,
     pub inner: ArrayExpressionInner<'ast, T>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
-pub struct ArrayValue<'ast, T>(pub Vec<TypedExpressionOrSpread<'ast, T>>);
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ArrayValue<'ast, T>(#[serde(borrow)] pub Vec<TypedExpressionOrSpread<'ast, T>>);
 
 impl<'ast, T> From<Vec<TypedExpressionOrSpread<'ast, T>>> for ArrayValue<'ast, T> {
     fn from(array: Vec<TypedExpressionOrSpread<'ast, T>>) -> Self {
@@ -1489,8 +1543,9 @@ impl<'ast, T> std::iter::FromIterator<TypedExpressionOrSpread<'ast, T>> for Arra
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ArrayExpressionInner<'ast, T> {
+    #[serde(borrow)]
     Block(BlockExpression<'ast, T, ArrayExpression<'ast, T>>),
     Identifier(IdentifierExpression<'ast, ArrayExpression<'ast, T>>),
     Value(ArrayValue<'ast, T>),
@@ -1530,8 +1585,9 @@ impl<'ast, T: Clone> ArrayExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct StructExpression<'ast, T> {
+    #[serde(borrow)]
     ty: StructType<'ast, T>,
     inner: StructExpressionInner<'ast, T>,
 }
@@ -1554,8 +1610,9 @@ impl<'ast, T> StructExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum StructExpressionInner<'ast, T> {
+    #[serde(borrow)]
     Block(BlockExpression<'ast, T, StructExpression<'ast, T>>),
     Identifier(IdentifierExpression<'ast, StructExpression<'ast, T>>),
     Value(Vec<TypedExpression<'ast, T>>),
@@ -1572,8 +1629,9 @@ impl<'ast, T> StructExpressionInner<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TupleExpression<'ast, T> {
+    #[serde(borrow)]
     ty: TupleType<'ast, T>,
     inner: TupleExpressionInner<'ast, T>,
 }
@@ -1596,8 +1654,9 @@ impl<'ast, T> TupleExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Debug, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TupleExpressionInner<'ast, T> {
+    #[serde(borrow)]
     Block(BlockExpression<'ast, T, TupleExpression<'ast, T>>),
     Identifier(IdentifierExpression<'ast, TupleExpression<'ast, T>>),
     Value(Vec<TypedExpression<'ast, T>>),
@@ -2115,13 +2174,16 @@ pub enum ConditionalOrExpression<'ast, T, E: Expr<'ast, T>> {
     Expression(E::Inner),
 }
 
-pub trait Conditional<'ast, T> {
+pub trait Conditional<'ast, T>: Sized {
     fn conditional(
         condition: BooleanExpression<'ast, T>,
         consequence: Self,
         alternative: Self,
         kind: ConditionalKind,
     ) -> Self;
+
+    /// Returns the conditional expression this expression consists of, if any, or the expression itself otherwise
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self>;
 }
 
 impl<'ast, T> Conditional<'ast, T> for FieldElementExpression<'ast, T> {
@@ -2138,6 +2200,13 @@ impl<'ast, T> Conditional<'ast, T> for FieldElementExpression<'ast, T> {
             kind,
         ))
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self {
+            FieldElementExpression::Conditional(c) => Ok(c),
+            e => Err(e),
+        }
+    }
 }
 
 impl<'ast, T> Conditional<'ast, T> for IntExpression<'ast, T> {
@@ -2154,6 +2223,13 @@ impl<'ast, T> Conditional<'ast, T> for IntExpression<'ast, T> {
             kind,
         ))
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self {
+            IntExpression::Conditional(c) => Ok(c),
+            e => Err(e),
+        }
+    }
 }
 
 impl<'ast, T> Conditional<'ast, T> for BooleanExpression<'ast, T> {
@@ -2170,6 +2246,13 @@ impl<'ast, T> Conditional<'ast, T> for BooleanExpression<'ast, T> {
             kind,
         ))
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self {
+            BooleanExpression::Conditional(c) => Ok(c),
+            e => Err(e),
+        }
+    }
 }
 
 impl<'ast, T> Conditional<'ast, T> for UExpression<'ast, T> {
@@ -2189,6 +2272,13 @@ impl<'ast, T> Conditional<'ast, T> for UExpression<'ast, T> {
         ))
         .annotate(bitwidth)
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self.inner {
+            UExpressionInner::Conditional(c) => Ok(c),
+            _ => Err(self),
+        }
+    }
 }
 
 impl<'ast, T: Clone> Conditional<'ast, T> for ArrayExpression<'ast, T> {
@@ -2208,6 +2298,13 @@ impl<'ast, T: Clone> Conditional<'ast, T> for ArrayExpression<'ast, T> {
         ))
         .annotate(ty, size)
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self.inner {
+            ArrayExpressionInner::Conditional(c) => Ok(c),
+            _ => Err(self),
+        }
+    }
 }
 
 impl<'ast, T: Clone> Conditional<'ast, T> for StructExpression<'ast, T> {
@@ -2226,6 +2323,13 @@ impl<'ast, T: Clone> Conditional<'ast, T> for StructExpression<'ast, T> {
         ))
         .annotate(ty)
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self.inner {
+            StructExpressionInner::Conditional(c) => Ok(c),
+            _ => Err(self),
+        }
+    }
 }
 
 impl<'ast, T: Clone> Conditional<'ast, T> for TupleExpression<'ast, T> {
@@ -2244,6 +2348,13 @@ impl<'ast, T: Clone> Conditional<'ast, T> for TupleExpression<'ast, T> {
         ))
         .annotate(ty)
     }
+
+    fn into_conditional(self) -> Result<ConditionalExpression<'ast, T, Self>, Self> {
+        match self.inner {
+            TupleExpressionInner::Conditional(c) => Ok(c),
+            _ => Err(self),
+        }
+    }
 }
 
 pub trait Select<'ast, T> {
@@ -2834,3 +2945,153 @@ impl<'ast, T: Field> Constant for TypedExpression<'ast, T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typed::types::{ConcreteStructMember, StructLocation};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn serde_json_round_trip() {
+        let a = || FieldElementExpression::<Bn128Field>::identifier("a".into());
+
+        // return a * 42 + a
+        let main: TypedFunction<Bn128Field> = TypedFunction {
+            arguments: vec![DeclarationVariable::field_element("a").into()],
+            statements: vec![TypedStatement::Return(
+                (a() * FieldElementExpression::Number(Bn128Field::from(42)) + a()).into(),
+            )],
+            signature: DeclarationSignature::new()
+                .inputs(vec![DeclarationType::FieldElement])
+                .output(DeclarationType::FieldElement),
+        };
+
+        let p = TypedProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                TypedModule {
+                    symbols: vec![TypedFunctionSymbolDeclaration::new(
+                        DeclarationFunctionKey::with_location("main", "main").signature(
+                            DeclarationSignature::new()
+                                .inputs(vec![DeclarationType::FieldElement])
+                                .output(DeclarationType::FieldElement),
+                        ),
+                        TypedFunctionSymbol::Here(main),
+                    )
+                    .into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let json = p.to_json().unwrap();
+        let de_p = TypedProgram::<Bn128Field>::from_json(&json).unwrap();
+
+        assert_eq!(p, de_p);
+    }
+
+    #[test]
+    fn serde_json_round_trip_struct() {
+        let mut foo = ConcreteStructType::new(
+            "lib".into(),
+            "Foo".into(),
+            vec![],
+            vec![
+                ConcreteStructMember::new("a".into(), ConcreteType::FieldElement),
+                ConcreteStructMember::new("b".into(), ConcreteType::Boolean),
+            ],
+        );
+        // the struct is imported under an alias
+        foo.location = Some(StructLocation {
+            module: "main".into(),
+            name: "Bar".into(),
+        });
+
+        let declaration_ty = DeclarationType::Struct(foo.clone().into());
+
+        // return f.a
+        let main: TypedFunction<Bn128Field> = TypedFunction {
+            arguments: vec![DeclarationVariable::struc("f", foo.clone().into()).into()],
+            statements: vec![TypedStatement::Return(
+                FieldElementExpression::member(
+                    StructExpressionInner::Identifier("f".into()).annotate(foo.clone().into()),
+                    "a".into(),
+                )
+                .into(),
+            )],
+            signature: DeclarationSignature::new()
+                .inputs(vec![declaration_ty.clone()])
+                .output(DeclarationType::FieldElement),
+        };
+
+        let p = TypedProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                TypedModule {
+                    symbols: vec![TypedFunctionSymbolDeclaration::new(
+                        DeclarationFunctionKey::with_location("main", "main").signature(
+                            DeclarationSignature::new()
+                                .inputs(vec![declaration_ty])
+                                .output(DeclarationType::FieldElement),
+                        ),
+                        TypedFunctionSymbol::Here(main),
+                    )
+                    .into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let json = p.to_json().unwrap();
+        let de_p = TypedProgram::<Bn128Field>::from_json(&json).unwrap();
+
+        assert_eq!(p, de_p);
+
+        // struct equality only looks at the canonical location, so check the alias separately
+        let de_foo: ConcreteStructType = with_struct_locations(|| {
+            serde_json::from_str(&serde_json::to_string(&foo).unwrap()).unwrap()
+        });
+        assert_eq!(de_foo.location, foo.location);
+
+        // the default encoding, shared with the ABI, leaves the locations out
+        let de_p: TypedProgram<Bn128Field> =
+            serde_json::from_str(&serde_json::to_string(&p).unwrap()).unwrap();
+        assert_ne!(p, de_p);
+    }
+
+    #[test]
+    fn serde_json_rejects_int() {
+        // integer literals are inferred during semantic checking, so a checked program never contains `Int`
+        let main: TypedFunction<Bn128Field> = TypedFunction {
+            arguments: vec![],
+            statements: vec![TypedStatement::definition(
+                Variable::new("a", Type::Int, false).into(),
+                IntExpression::Value(42u32.into()).into(),
+            )],
+            signature: DeclarationSignature::new(),
+        };
+
+        let p = TypedProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                TypedModule {
+                    symbols: vec![TypedFunctionSymbolDeclaration::new(
+                        DeclarationFunctionKey::with_location("main", "main"),
+                        TypedFunctionSymbol::Here(main),
+                    )
+                    .into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        assert!(p.to_json().is_err());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::typed::types::DeclarationConstant;
 use crate::typed::GVariable;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct GParameter<'ast, S> {
+    #[serde(borrow)]
     pub id: GVariable<'ast, S>,
     pub private: bool,
 }
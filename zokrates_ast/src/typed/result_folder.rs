@@ -197,7 +197,7 @@ pub trait ResultFolder<'ast, T: Field>: Sized {
     }
 
     fn fold_conditional_expression<
-        E: Expr<'ast, T> + PartialEq + Conditional<'ast, T> + ResultFold<'ast, T>,
+        E: Expr<'ast, T> + PartialEq + Constant + Conditional<'ast, T> + ResultFold<'ast, T>,
     >(
         &mut self,
         ty: &E::Ty,
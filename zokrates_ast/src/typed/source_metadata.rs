@@ -0,0 +1,100 @@
+// Collect the source locations attached to a typed program
+
+use crate::common::SourceMetadata;
+use crate::typed::folder::*;
+use crate::typed::*;
+use zokrates_field::Field;
+
+#[derive(Default)]
+struct SourceMetadataCollector {
+    metadata: Vec<SourceMetadata>,
+}
+
+impl<'ast, T: Field> TypedProgram<'ast, T> {
+    /// Returns the source locations of the assertions and assembly constraints of this program, in program order
+    pub fn collect_source_metadata(&self) -> Vec<SourceMetadata> {
+        let mut collector = SourceMetadataCollector::default();
+        collector.fold_program(self.clone());
+        collector.metadata
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for SourceMetadataCollector {
+    fn fold_assembly_statement(
+        &mut self,
+        s: TypedAssemblyStatement<'ast, T>,
+    ) -> Vec<TypedAssemblyStatement<'ast, T>> {
+        if let TypedAssemblyStatement::Constraint(_, _, metadata) = &s {
+            self.metadata.push(metadata.clone());
+        }
+        fold_assembly_statement(self, s)
+    }
+
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        if let TypedStatement::Assertion(_, RuntimeError::SourceAssertion(metadata)) = &s {
+            self.metadata.push(metadata.clone());
+        }
+        fold_statement(self, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::untyped::Position;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn collect_assertions() {
+        let metadata = |line| SourceMetadata::new("main.zok".into(), Position { line, col: 5 });
+
+        // assert(a == 1);
+        // assert(a != 2);
+        // return a;
+        let main: TypedFunction<Bn128Field> = TypedFunction {
+            arguments: vec![DeclarationVariable::field_element("a").into()],
+            statements: vec![
+                TypedStatement::Assertion(
+                    BooleanExpression::FieldEq(EqExpression::new(
+                        FieldElementExpression::identifier("a".into()),
+                        FieldElementExpression::Number(Bn128Field::from(1)),
+                    )),
+                    RuntimeError::SourceAssertion(metadata(2)),
+                ),
+                TypedStatement::Assertion(
+                    BooleanExpression::Not(box BooleanExpression::FieldEq(EqExpression::new(
+                        FieldElementExpression::identifier("a".into()),
+                        FieldElementExpression::Number(Bn128Field::from(2)),
+                    ))),
+                    RuntimeError::SourceAssertion(metadata(3)),
+                ),
+                TypedStatement::Return(FieldElementExpression::identifier("a".into()).into()),
+            ],
+            signature: DeclarationSignature::new()
+                .inputs(vec![DeclarationType::FieldElement])
+                .output(DeclarationType::FieldElement),
+        };
+
+        let p = TypedProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                TypedModule {
+                    symbols: vec![TypedFunctionSymbolDeclaration::new(
+                        DeclarationFunctionKey::with_location("main", "main").signature(
+                            DeclarationSignature::new()
+                                .inputs(vec![DeclarationType::FieldElement])
+                                .output(DeclarationType::FieldElement),
+                        ),
+                        TypedFunctionSymbol::Here(main),
+                    )
+                    .into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        assert_eq!(p.collect_source_metadata(), vec![metadata(2), metadata(3)]);
+    }
+}
@@ -3,6 +3,7 @@ use crate::typed::{
 };
 use crate::typed::{TryFrom, TryInto};
 use serde::{de::Error, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -43,8 +44,9 @@ impl<S> IntoType<S> for UBitwidth {
     }
 }
 
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct GenericIdentifier<'ast> {
+    #[serde(borrow)]
     name: Option<&'ast str>,
     index: usize,
 }
@@ -142,8 +144,9 @@ impl<'ast> CanonicalConstantIdentifier<'ast> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum DeclarationConstant<'ast, T> {
+    #[serde(borrow)]
     Generic(GenericIdentifier<'ast>),
     Concrete(u32),
     Constant(CanonicalConstantIdentifier<'ast>),
@@ -468,9 +471,40 @@ impl<S> TryFrom<GType<S>> for GTupleType<S> {
     }
 }
 
+thread_local! {
+    static STRUCT_LOCATIONS: Cell<bool> = Cell::new(false);
+}
+
+/// Run `f` with the source locations of structs included in the serde encoding of types.
+/// They are left out by default, as the ABI shares this encoding and must not depend on where the program lives
+pub fn with_struct_locations<R, F: FnOnce() -> R>(f: F) -> R {
+    let previous = STRUCT_LOCATIONS.with(|l| l.replace(true));
+    let res = f();
+    STRUCT_LOCATIONS.with(|l| l.set(previous));
+    res
+}
+
+fn skip_struct_location<U>(_: &U) -> bool {
+    !STRUCT_LOCATIONS.with(|l| l.get())
+}
+
+fn deserialize_struct_location<'de, D: Deserializer<'de>, U: Deserialize<'de> + Default>(
+    d: D,
+) -> Result<U, D::Error> {
+    let location = U::deserialize(d)?;
+    Ok(match STRUCT_LOCATIONS.with(|l| l.get()) {
+        true => location,
+        false => U::default(),
+    })
+}
+
 #[derive(Debug, Clone, Hash, Serialize, Deserialize, PartialOrd, Ord, Eq, PartialEq)]
 pub struct StructLocation {
-    #[serde(skip)]
+    #[serde(
+        default,
+        skip_serializing_if = "skip_struct_location",
+        deserialize_with = "deserialize_struct_location"
+    )]
     pub module: PathBuf,
     pub name: String,
 }
@@ -485,7 +519,11 @@ impl<'ast, T> From<ConcreteArrayType> for DeclarationArrayType<'ast, T> {
 pub struct GStructType<S> {
     #[serde(flatten)]
     pub canonical_location: StructLocation,
-    #[serde(skip)]
+    #[serde(
+        default,
+        skip_serializing_if = "skip_struct_location",
+        deserialize_with = "deserialize_struct_location"
+    )]
     pub location: Option<StructLocation>,
     pub generics: Vec<Option<S>>,
     pub members: Vec<GStructMember<S>>,
@@ -965,9 +1003,10 @@ impl ConcreteType {
 
 pub type FunctionIdentifier<'ast> = &'ast str;
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct GFunctionKey<'ast, S> {
     pub module: OwnedTypedModuleId,
+    #[serde(borrow)]
     pub id: FunctionIdentifier<'ast>,
     pub signature: GSignature<S>,
 }
@@ -1008,6 +1047,26 @@ impl<'ast, S: fmt::Display> fmt::Display for GGenericsAssignment<'ast, S> {
     }
 }
 
+// generic identifiers are not valid map keys in formats like JSON, so we encode the assignment as a list of pairs
+impl<'ast, S: Serialize> Serialize for GGenericsAssignment<'ast, S> {
+    fn serialize<Z>(&self, s: Z) -> Result<<Z as Serializer>::Ok, <Z as Serializer>::Error>
+    where
+        Z: Serializer,
+    {
+        s.collect_seq(self.0.iter())
+    }
+}
+
+impl<'de: 'ast, 'ast, S: Deserialize<'de>> Deserialize<'de> for GGenericsAssignment<'ast, S> {
+    fn deserialize<D>(d: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<(GenericIdentifier<'ast>, S)>::deserialize(d)
+            .map(|pairs| GGenericsAssignment(pairs.into_iter().collect()))
+    }
+}
+
 impl<'ast, T> PartialEq<DeclarationFunctionKey<'ast, T>> for ConcreteFunctionKey<'ast> {
     fn eq(&self, other: &DeclarationFunctionKey<'ast, T>) -> bool {
         self.module == other.module && self.id == other.id && self.signature == other.signature
@@ -1,5 +1,6 @@
 use crate::typed::types::UBitwidth;
 use crate::typed::*;
+use serde::{Deserialize, Serialize};
 use std::ops::{Add, Div, Mul, Neg, Not, Rem, Sub};
 use zokrates_field::Field;
 
@@ -127,16 +128,17 @@ impl<'ast, T: Field> From<u128> for UExpressionInner<'ast, T> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct UMetadata {
     pub bitwidth: Option<Bitwidth>,
     pub should_reduce: Option<bool>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct UExpression<'ast, T> {
     pub bitwidth: UBitwidth,
     pub metadata: Option<UMetadata>,
+    #[serde(borrow)]
     pub inner: UExpressionInner<'ast, T>,
 }
 
@@ -161,8 +163,9 @@ impl<'ast, T> PartialEq<u32> for UExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum UExpressionInner<'ast, T> {
+    #[serde(borrow)]
     Block(BlockExpression<'ast, T, UExpression<'ast, T>>),
     Identifier(IdentifierExpression<'ast, UExpression<'ast, T>>),
     Value(u128),
@@ -1,7 +1,9 @@
 use super::{
     ArrayExpression, ArrayExpressionInner, ArrayValue, BooleanExpression, Conditional,
-    ConditionalKind, Expr, FieldElementExpression, Id, Identifier, Select, Typed, TypedExpression,
-    TypedExpressionOrSpread, UBitwidth, UExpression, UExpressionInner,
+    ConditionalKind, DeclarationFunctionKey, DeclarationParameter, DeclarationSignature,
+    DeclarationType, Expr, FieldElementExpression, Id, Identifier, Select, Typed, TypedExpression,
+    TypedExpressionOrSpread, TypedFunction, TypedFunctionSymbol, TypedFunctionSymbolDeclaration,
+    TypedModule, TypedProgram, TypedStatement, UBitwidth, UExpression, UExpressionInner,
 };
 
 use zokrates_field::Field;
@@ -64,3 +66,72 @@ pub fn select<
         index.try_into().map_err(|_| ()).unwrap(),
     )
 }
+
+/// Builds a program made of a single `main` module defining a `main` function
+pub struct TypedProgramBuilder<'ast, T> {
+    arguments: Vec<DeclarationParameter<'ast, T>>,
+    statements: Vec<TypedStatement<'ast, T>>,
+    output: Option<DeclarationType<'ast, T>>,
+}
+
+impl<'ast, T: Field> Default for TypedProgramBuilder<'ast, T> {
+    fn default() -> Self {
+        TypedProgramBuilder {
+            arguments: vec![],
+            statements: vec![],
+            output: None,
+        }
+    }
+}
+
+impl<'ast, T: Field> TypedProgramBuilder<'ast, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn argument<P: Into<DeclarationParameter<'ast, T>>>(mut self, argument: P) -> Self {
+        self.arguments.push(argument.into());
+        self
+    }
+
+    pub fn statement(mut self, statement: TypedStatement<'ast, T>) -> Self {
+        self.statements.push(statement);
+        self
+    }
+
+    pub fn output(mut self, output: DeclarationType<'ast, T>) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    pub fn build(self) -> TypedProgram<'ast, T> {
+        let signature = DeclarationSignature::new()
+            .inputs(self.arguments.iter().map(|a| a.id._type.clone()).collect());
+        let signature = match self.output {
+            Some(output) => signature.output(output),
+            None => signature,
+        };
+
+        let main = TypedFunction {
+            arguments: self.arguments,
+            statements: self.statements,
+            signature: signature.clone(),
+        };
+
+        TypedProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                TypedModule {
+                    symbols: vec![TypedFunctionSymbolDeclaration::new(
+                        DeclarationFunctionKey::with_location("main", "main").signature(signature),
+                        TypedFunctionSymbol::Here(main),
+                    )
+                    .into()],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
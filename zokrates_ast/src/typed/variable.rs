@@ -3,10 +3,12 @@ use crate::typed::types::{GType, SpecializationError};
 use crate::typed::Identifier;
 use crate::typed::UExpression;
 use crate::typed::{TryFrom, TryInto};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, PartialEq, Hash, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, PartialEq, Hash, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub struct GVariable<'ast, S> {
+    #[serde(borrow)]
     pub id: Identifier<'ast>,
     pub _type: GType<S>,
     pub is_mutable: bool,
@@ -6,7 +6,9 @@ use pairing::{ff::to_hex, CurveAffine, Engine};
 
 use zokrates_field::BellmanFieldExtensions;
 use zokrates_field::Field;
-use zokrates_proof_systems::{Backend, MpcBackend, NonUniversalBackend, Proof, SetupKeypair};
+use zokrates_proof_systems::{
+    Backend, BackendError, MpcBackend, NonUniversalBackend, Proof, SetupKeypair,
+};
 
 use crate::Computation;
 use crate::{get_random_seed, Bellman};
@@ -20,14 +22,15 @@ use zokrates_proof_systems::groth16::{ProofPoints, VerificationKey, G16};
 use zokrates_proof_systems::Scheme;
 
 impl<T: Field + BellmanFieldExtensions> Backend<T, G16> for Bellman {
-    fn generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
+    fn try_generate_proof<'a, I: IntoIterator<Item = Statement<'a, T>>, R: RngCore + CryptoRng>(
         program: ProgIterator<'a, T, I>,
         witness: Witness<T>,
         proving_key: Vec<u8>,
         rng: &mut R,
-    ) -> Proof<T, G16> {
+    ) -> Result<Proof<T, G16>, BackendError> {
         let computation = Computation::with_witness(program, witness);
-        let params = Parameters::read(proving_key.as_slice(), true).unwrap();
+        let params = Parameters::read(proving_key.as_slice(), true)
+            .map_err(|e| BackendError::ProvingKey(e.to_string()))?;
 
         let public_inputs: Vec<String> = computation
             .public_inputs_values()
@@ -42,29 +45,32 @@ impl<T: Field + BellmanFieldExtensions> Backend<T, G16> for Bellman {
             c: parse_g1::<T>(&proof.c),
         };
 
-        Proof::new(proof_points, public_inputs)
+        Ok(Proof::new(proof_points, public_inputs))
     }
 
-    fn verify(vk: <G16 as Scheme<T>>::VerificationKey, proof: Proof<T, G16>) -> bool {
+    fn try_verify(
+        vk: <G16 as Scheme<T>>::VerificationKey,
+        proof: Proof<T, G16>,
+    ) -> Result<bool, BackendError> {
         let vk = VerifyingKey {
-            alpha_g1: serialization::to_g1::<T>(vk.alpha),
+            alpha_g1: serialization::to_g1::<T>(vk.alpha)?,
             beta_g1: <T::BellmanEngine as Engine>::G1Affine::one(), // not used during verification
-            beta_g2: serialization::to_g2::<T>(vk.beta),
-            gamma_g2: serialization::to_g2::<T>(vk.gamma),
+            beta_g2: serialization::to_g2::<T>(vk.beta)?,
+            gamma_g2: serialization::to_g2::<T>(vk.gamma)?,
             delta_g1: <T::BellmanEngine as Engine>::G1Affine::one(), // not used during verification
-            delta_g2: serialization::to_g2::<T>(vk.delta),
+            delta_g2: serialization::to_g2::<T>(vk.delta)?,
             ic: vk
                 .gamma_abc
                 .into_iter()
                 .map(serialization::to_g1::<T>)
-                .collect(),
+                .collect::<Result<_, _>>()?,
         };
 
         let pvk: PreparedVerifyingKey<T::BellmanEngine> = prepare_verifying_key(&vk);
         let bellman_proof = BellmanProof {
-            a: serialization::to_g1::<T>(proof.proof.a),
-            b: serialization::to_g2::<T>(proof.proof.b),
-            c: serialization::to_g1::<T>(proof.proof.c),
+            a: serialization::to_g1::<T>(proof.proof.a)?,
+            b: serialization::to_g2::<T>(proof.proof.b)?,
+            c: serialization::to_g1::<T>(proof.proof.c)?,
         };
 
         let public_inputs: Vec<_> = proof
@@ -72,12 +78,13 @@ impl<T: Field + BellmanFieldExtensions> Backend<T, G16> for Bellman {
             .iter()
             .map(|s| {
                 T::try_from_str(s.trim_start_matches("0x"), 16)
-                    .unwrap()
-                    .into_bellman()
+                    .map(|e| e.into_bellman())
+                    .map_err(|_| BackendError::Encoding(format!("`{}` is not a field element", s)))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<_, _>>()?;
 
-        verify_proof(&pvk, &bellman_proof, &public_inputs).unwrap()
+        verify_proof(&pvk, &bellman_proof, &public_inputs)
+            .map_err(|e| BackendError::Backend(e.to_string()))
     }
 }
 
@@ -175,24 +182,39 @@ pub mod serialization {
         }
     }
 
+    fn fq<T: BellmanFieldExtensions>(
+        value: &str,
+    ) -> Result<<T::BellmanEngine as Engine>::Fq, BackendError> {
+        from_hex(value).map_err(|e| BackendError::Encoding(format!("`{}`: {}", value, e)))
+    }
+
     pub fn to_g1<T: BellmanFieldExtensions>(
         g1: G1Affine,
-    ) -> <T::BellmanEngine as Engine>::G1Affine {
-        <T::BellmanEngine as Engine>::G1Affine::from_xy_unchecked(
-            from_hex(&g1.0).unwrap(),
-            from_hex(&g1.1).unwrap(),
-        )
+    ) -> Result<<T::BellmanEngine as Engine>::G1Affine, BackendError> {
+        Ok(<T::BellmanEngine as Engine>::G1Affine::from_xy_unchecked(
+            fq::<T>(&g1.0)?,
+            fq::<T>(&g1.1)?,
+        ))
     }
     pub fn to_g2<T: BellmanFieldExtensions>(
         g2: G2Affine,
-    ) -> <T::BellmanEngine as Engine>::G2Affine {
+    ) -> Result<<T::BellmanEngine as Engine>::G2Affine, BackendError> {
         match g2 {
             G2Affine::Fq2(g2) => {
+                // check the coordinates first, as `new_fq2` panics on invalid ones
+                for c in [&(g2.0).0, &(g2.0).1, &(g2.1).0, &(g2.1).1] {
+                    fq::<T>(c)?;
+                }
+
                 let x = T::new_fq2(&(g2.0).0, &(g2.0).1);
                 let y = T::new_fq2(&(g2.1).0, &(g2.1).1);
-                <T::BellmanEngine as Engine>::G2Affine::from_xy_unchecked(x, y)
+                Ok(<T::BellmanEngine as Engine>::G2Affine::from_xy_unchecked(
+                    x, y,
+                ))
             }
-            _ => unreachable!(),
+            G2Affine::Fq(_) => Err(BackendError::Encoding(
+                "expected a G2 point over Fq2".to_string(),
+            )),
         }
     }
 }
@@ -1,8 +1,13 @@
 mod r1cs;
 mod witness;
 
-pub use r1cs::write_r1cs;
-pub use witness::write_witness;
+pub use r1cs::{
+    check_public_inputs, compose_r1cs, constraints_per_statement, prepare_witness, r1cs_program,
+    r1cs_program_to_sink, r1cs_program_with_hints, r1cs_program_with_limit, reorder_public_inputs,
+    verify_witness, witness_vector, write_matrixmarket, write_r1cs, write_r1cs_text, Error, Matrix,
+    R1csSink, VariableHint,
+};
+pub use witness::{read_witness, write_witness};
 
 #[cfg(test)]
 mod tests {
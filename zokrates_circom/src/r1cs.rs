@@ -1,10 +1,12 @@
 use byteorder::{LittleEndian, WriteBytesExt};
 use std::collections::{BTreeSet, HashMap};
+use std::hash::BuildHasher;
 use std::io::Result;
 use std::{io::Write, ops::Add};
+use zokrates_ast::common::VariableMap;
 use zokrates_ast::flat::Variable;
-use zokrates_ast::ir::{Prog, Statement};
-use zokrates_field::Field;
+use zokrates_ast::ir::{self, Prog, QuadComb, Solver, Statement, Witness};
+use zokrates_field::{Field, Pow};
 struct Header {
     pub field_size: u32,
     pub prime_size: Vec<u8>,
@@ -38,11 +40,62 @@ fn write_header<W: Write>(writer: &mut W, header: Header) -> Result<()> {
 ///
 /// * `variables` - A mutual map that maps all existing variables to their index.
 /// * `var` - Variable to be searched for.
-pub fn provide_variable_idx(variables: &mut HashMap<Variable, usize>, var: &Variable) -> usize {
+pub fn provide_variable_idx<S: BuildHasher>(
+    variables: &mut HashMap<Variable, usize, S>,
+    var: &Variable,
+) -> usize {
     let index = variables.len();
     *variables.entry(*var).or_insert(index)
 }
 
+/// A destination for the rows of an R1CS representation, so that backends can consume it without building the full matrices.
+/// Variables are referred to by their index in the variable list returned along with the output.
+pub trait R1csSink<T> {
+    type Output;
+
+    /// Adds `value * x[index]` to the `A` linear combination of constraint `row`
+    fn push_a(&mut self, row: usize, index: usize, value: T);
+    /// Adds `value * x[index]` to the `B` linear combination of constraint `row`
+    fn push_b(&mut self, row: usize, index: usize, value: T);
+    /// Adds `value * x[index]` to the `C` linear combination of constraint `row`
+    fn push_c(&mut self, row: usize, index: usize, value: T);
+    /// Called once all constraints have been pushed
+    fn finish(self) -> Self::Output;
+}
+
+/// A sink collecting the constraints in memory, as returned by `r1cs_program`
+struct ConstraintsSink<T> {
+    constraints: Vec<Constraint<T>>,
+}
+
+impl<T> ConstraintsSink<T> {
+    fn with_rows(rows: usize) -> Self {
+        ConstraintsSink {
+            constraints: (0..rows).map(|_| (vec![], vec![], vec![])).collect(),
+        }
+    }
+}
+
+impl<T> R1csSink<T> for ConstraintsSink<T> {
+    type Output = Vec<Constraint<T>>;
+
+    fn push_a(&mut self, row: usize, index: usize, value: T) {
+        self.constraints[row].0.push((index, value));
+    }
+
+    fn push_b(&mut self, row: usize, index: usize, value: T) {
+        self.constraints[row].1.push((index, value));
+    }
+
+    fn push_c(&mut self, row: usize, index: usize, value: T) {
+        self.constraints[row].2.push((index, value));
+    }
+
+    fn finish(self) -> Self::Output {
+        self.constraints
+    }
+}
+
 /// Calculates one R1CS row representation of a program and returns (V, A, B, C) so that:
 /// * `V` contains all used variables and the index in the vector represents the used number in `A`, `B`, `C`
 /// * `<A,x>*<B,x> = <C,x>` for a witness `x`
@@ -51,7 +104,21 @@ pub fn provide_variable_idx(variables: &mut HashMap<Variable, usize>, var: &Vari
 ///
 /// * `prog` - The program the representation is calculated for.
 pub fn r1cs_program<T: Field>(prog: Prog<T>) -> (Vec<Variable>, usize, Vec<Constraint<T>>) {
-    let mut variables: HashMap<Variable, usize> = HashMap::new();
+    let rows = prog.constraint_count();
+    r1cs_program_to_sink(prog, ConstraintsSink::with_rows(rows))
+}
+
+/// Same as `r1cs_program`, but pushes the constraints to `sink` and returns its output instead of the constraints
+///
+/// # Arguments
+///
+/// * `prog` - The program the representation is calculated for.
+/// * `sink` - The sink receiving the constraints.
+pub fn r1cs_program_to_sink<T: Field, S: R1csSink<T>>(
+    prog: Prog<T>,
+    mut sink: S,
+) -> (Vec<Variable>, usize, S::Output) {
+    let mut variables: VariableMap<usize> = VariableMap::default();
     provide_variable_idx(&mut variables, &Variable::one());
 
     for i in 0..prog.return_count {
@@ -91,31 +158,27 @@ pub fn r1cs_program<T: Field>(prog: Prog<T>) -> (Vec<Variable>, usize, Vec<Const
         provide_variable_idx(&mut variables, variable);
     }
 
-    let mut constraints = vec![];
-
-    // second pass to convert program to raw sparse vectors
-    for (quad, lin) in prog.statements.into_iter().filter_map(|s| match s {
-        Statement::Constraint(quad, lin, _) => Some((quad, lin)),
-        Statement::Block(..) => unreachable!(),
-        Statement::Directive(..) => None,
-        Statement::Log(..) => None,
-    }) {
-        constraints.push((
-            quad.left
-                .0
-                .into_iter()
-                .map(|(k, v)| (*variables.get(&k).unwrap(), v))
-                .collect(),
-            quad.right
-                .0
-                .into_iter()
-                .map(|(k, v)| (*variables.get(&k).unwrap(), v))
-                .collect(),
-            lin.0
-                .into_iter()
-                .map(|(k, v)| (*variables.get(&k).unwrap(), v))
-                .collect(),
-        ));
+    // second pass to push the program to the sink as raw sparse vectors
+    for (row, (quad, lin)) in prog
+        .statements
+        .into_iter()
+        .filter_map(|s| match s {
+            Statement::Constraint(quad, lin, _) => Some((quad, lin)),
+            Statement::Block(..) => unreachable!(),
+            Statement::Directive(..) => None,
+            Statement::Log(..) => None,
+        })
+        .enumerate()
+    {
+        for (k, v) in quad.left.0 {
+            sink.push_a(row, *variables.get(&k).unwrap(), v);
+        }
+        for (k, v) in quad.right.0 {
+            sink.push_b(row, *variables.get(&k).unwrap(), v);
+        }
+        for (k, v) in lin.0 {
+            sink.push_c(row, *variables.get(&k).unwrap(), v);
+        }
     }
 
     // Convert map back into list ordered by index
@@ -124,10 +187,557 @@ pub fn r1cs_program<T: Field>(prog: Prog<T>) -> (Vec<Variable>, usize, Vec<Const
         assert_eq!(variables_list[v], Variable::new(0));
         variables_list[v] = k;
     }
-    (variables_list, private_inputs_offset, constraints)
+    (variables_list, private_inputs_offset, sink.finish())
+}
+
+/// A property of a variable which a backend can use to emit tighter constraints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableHint {
+    /// The variable is either 0 or 1
+    Boolean,
+    /// The variable is smaller than `2^bits`
+    RangeBounded(usize),
+}
+
+/// Returns the hint implied by the constraint `quad == lin` for the variable it defines, if any
+fn constraint_hint<T: Field>(
+    quad: &QuadComb<T>,
+    lin: &ir::LinComb<T>,
+    hints: &HashMap<Variable, VariableHint>,
+    powers: &[T],
+) -> Option<(Variable, VariableHint)> {
+    let v = match lin.clone().try_summand() {
+        Ok((v, c)) if c == T::one() => v,
+        _ => return None,
+    };
+
+    // `v * v == v`
+    if quad.left == quad.right
+        && matches!(quad.left.clone().try_summand(), Ok((w, c)) if w == v && c == T::one())
+    {
+        return Some((v, VariableHint::Boolean));
+    }
+
+    // `sum(2**i * b_i) == v` where all `b_i` are booleans and all `i` are distinct
+    let sum = quad.clone().try_linear().ok()?;
+
+    let exponents = sum
+        .0
+        .iter()
+        .map(|(b, c)| match hints.get(b) {
+            Some(VariableHint::Boolean) => powers.iter().position(|p| p == c),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // a repeated power, as in `b1 + b2 == v`, can carry over to the next bit
+    let mut seen = BTreeSet::new();
+    if !exponents.iter().all(|i| seen.insert(*i)) {
+        return None;
+    }
+
+    exponents
+        .into_iter()
+        .max()
+        .map(|i| (v, VariableHint::RangeBounded(i + 1)))
+}
+
+/// Derives hints for the variables of `statements` from the statements defining them
+fn variable_hints<T: Field>(statements: &[Statement<T>]) -> HashMap<Variable, VariableHint> {
+    let powers: Vec<T> = (0..T::get_required_bits())
+        .map(|i| T::from(2).pow(i))
+        .collect();
+
+    let mut hints = HashMap::new();
+
+    for s in statements {
+        match s {
+            // the outputs of a bit decomposition are bits
+            Statement::Directive(d) if matches!(d.solver, Solver::Bits(_)) => {
+                for o in &d.outputs {
+                    hints.insert(*o, VariableHint::Boolean);
+                }
+            }
+            Statement::Constraint(quad, lin, _) => {
+                if let Some((v, hint)) = constraint_hint(quad, lin, &hints, &powers) {
+                    hints.entry(v).or_insert(hint);
+                }
+            }
+            Statement::Block(..) => unreachable!(),
+            _ => {}
+        }
+    }
+
+    hints
+}
+
+/// Same as `r1cs_program`, but also returns hints about the variables, keyed by their index in `V`
+///
+/// # Arguments
+///
+/// * `prog` - The program the representation is calculated for.
+#[allow(clippy::type_complexity)]
+pub fn r1cs_program_with_hints<T: Field>(
+    prog: Prog<T>,
+) -> (
+    Vec<Variable>,
+    usize,
+    Vec<Constraint<T>>,
+    HashMap<usize, VariableHint>,
+) {
+    let hints = variable_hints(&prog.statements);
+
+    let (variables, private_inputs_offset, constraints) = r1cs_program(prog);
+
+    let hints = variables
+        .iter()
+        .enumerate()
+        .filter_map(|(index, v)| hints.get(v).map(|hint| (index, *hint)))
+        .collect();
+
+    (variables, private_inputs_offset, constraints, hints)
+}
+
+/// Attributes the constraints returned by `r1cs_program` to the statements of `prog` which generate them,
+/// as a list of `(statement index, constraint count)` pairs in statement order
+///
+/// # Arguments
+///
+/// * `prog` - The program to profile.
+pub fn constraints_per_statement<T: Field>(prog: &Prog<T>) -> Vec<(usize, usize)> {
+    prog.statements
+        .iter()
+        .enumerate()
+        .map(|(index, s)| match s {
+            Statement::Constraint(..) => (index, 1),
+            Statement::Block(..) => unreachable!(),
+            Statement::Directive(..) | Statement::Log(..) => (index, 0),
+        })
+        .collect()
+}
+
+/// Same as `r1cs_program`, but fails early if the program has more than `max_constraints` constraints
+///
+/// # Arguments
+///
+/// * `prog` - The program the representation is calculated for.
+/// * `max_constraints` - The maximum number of constraints allowed, `None` meaning no limit.
+#[allow(clippy::type_complexity)]
+pub fn r1cs_program_with_limit<T: Field>(
+    prog: Prog<T>,
+    max_constraints: Option<usize>,
+) -> std::result::Result<(Vec<Variable>, usize, Vec<Constraint<T>>), Error> {
+    if let Some(limit) = max_constraints {
+        let found = prog.constraint_count();
+        if found > limit {
+            return Err(Error::TooManyConstraints { limit, found });
+        }
+    }
+
+    Ok(r1cs_program(prog))
+}
+
+/// Checks that no public input of `prog` is declared twice, as `r1cs_program` would silently merge them
+///
+/// # Arguments
+///
+/// * `prog` - The program to check.
+pub fn check_public_inputs<T: Field>(prog: &Prog<T>) -> std::result::Result<(), Error> {
+    let mut seen = BTreeSet::default();
+
+    match prog
+        .arguments
+        .iter()
+        .filter(|p| !p.private)
+        .find(|p| !seen.insert(p.id))
+    {
+        Some(p) => Err(Error::DuplicatePublicInput(p.id)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    PermutationLength { expected: usize, found: usize },
+    PermutationIndex(usize),
+    TooManyConstraints { limit: usize, found: usize },
+    DuplicatePublicInput(Variable),
+    MissingWitnessValue(Variable),
+    UnsatisfiedConstraint(usize),
+    AssignmentLength { expected: usize, found: usize },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::PermutationLength { expected, found } => write!(
+                f,
+                "Permutation has length {}, expected {} (the number of public inputs)",
+                found, expected
+            ),
+            Error::PermutationIndex(i) => {
+                write!(f, "Public input index {} is out of range or repeated", i)
+            }
+            Error::TooManyConstraints { limit, found } => write!(
+                f,
+                "Program has {} constraints, which exceeds the limit of {}",
+                found, limit
+            ),
+            Error::DuplicatePublicInput(v) => {
+                write!(f, "Public input {} is declared more than once", v)
+            }
+            Error::MissingWitnessValue(v) => write!(f, "Witness has no value for {}", v),
+            Error::UnsatisfiedConstraint(i) => {
+                write!(f, "Witness does not satisfy constraint at row {}", i)
+            }
+            Error::AssignmentLength { expected, found } => write!(
+                f,
+                "Assignment has length {}, expected at least {} (the number of columns used by the constraints)",
+                found, expected
+            ),
+        }
+    }
+}
+
+/// Permutes the public part of an R1CS representation returned by `r1cs_program`
+/// so that the public input at position `i` becomes the one at position `permutation[i]` in the original layout.
+/// Constraints are rewritten accordingly, so a witness laid out in the new order satisfies them.
+///
+/// # Arguments
+///
+/// * `prepared` - The output of `r1cs_program`.
+/// * `permutation` - A permutation of `0..n` where `n` is the number of public inputs, excluding the constant `~one`.
+#[allow(clippy::type_complexity)]
+pub fn reorder_public_inputs<T: Field>(
+    prepared: (Vec<Variable>, usize, Vec<Constraint<T>>),
+    permutation: &[usize],
+) -> std::result::Result<(Vec<Variable>, usize, Vec<Constraint<T>>), Error> {
+    let (variables, private_inputs_offset, constraints) = prepared;
+
+    // the first variable is always `~one`, which is not part of the permutation
+    let public_count = private_inputs_offset - 1;
+
+    if permutation.len() != public_count {
+        return Err(Error::PermutationLength {
+            expected: public_count,
+            found: permutation.len(),
+        });
+    }
+
+    // maps old indices to new indices
+    let mut index_map: Vec<usize> = (0..variables.len()).collect();
+    let mut seen = vec![false; public_count];
+
+    for (new, &old) in permutation.iter().enumerate() {
+        if old >= public_count || seen[old] {
+            return Err(Error::PermutationIndex(old));
+        }
+        seen[old] = true;
+        index_map[old + 1] = new + 1;
+    }
+
+    let mut reordered_variables = variables.clone();
+    for (old, variable) in variables.into_iter().enumerate() {
+        reordered_variables[index_map[old]] = variable;
+    }
+
+    let remap =
+        |l: LinComb<T>| -> LinComb<T> { l.into_iter().map(|(i, c)| (index_map[i], c)).collect() };
+
+    let constraints = constraints
+        .into_iter()
+        .map(|(a, b, c)| (remap(a), remap(b), remap(c)))
+        .collect();
+
+    Ok((reordered_variables, private_inputs_offset, constraints))
+}
+
+/// Composes two R1CS representations returned by `r1cs_program` into one, sharing `~one` and the public inputs listed in `shared`.
+/// The other variables of `b` are renamed to fresh variables so that they do not collide with the ones of `a`.
+/// The public part of the result is the one of `a` followed by the public variables of `b` which are not shared,
+/// and the same goes for the private part.
+///
+/// # Arguments
+///
+/// * `a` - The first system.
+/// * `b` - The second system.
+/// * `shared` - The variables to identify between both systems. Variables which are not public in both systems are not shared.
+#[allow(clippy::type_complexity)]
+pub fn compose_r1cs<T: Field>(
+    a: (Vec<Variable>, usize, Vec<Constraint<T>>),
+    b: (Vec<Variable>, usize, Vec<Constraint<T>>),
+    shared: &[Variable],
+) -> (Vec<Variable>, usize, Vec<Constraint<T>>) {
+    let (a_variables, a_offset, a_constraints) = a;
+    let (b_variables, b_offset, b_constraints) = b;
+
+    // fresh variables are allocated after the largest ones used in `a`
+    let mut next_public = a_variables
+        .iter()
+        .filter(|v| v.is_output())
+        .map(|v| (-v.id) as usize)
+        .max()
+        .unwrap_or(0);
+    let mut next_private = a_variables
+        .iter()
+        .filter(|v| v.id > 0)
+        .map(|v| v.id() + 1)
+        .max()
+        .unwrap_or(0);
+    let mut rename = |v: &Variable| match v.is_output() {
+        true => {
+            next_public += 1;
+            Variable::public(next_public - 1)
+        }
+        false => {
+            next_private += 1;
+            Variable::new(next_private - 1)
+        }
+    };
+
+    let mut variables = a_variables[..a_offset].to_vec();
+
+    // the index of each variable of `b` in the composed system
+    let mut b_index = vec![0; b_variables.len()];
+
+    for (index, v) in b_variables.iter().enumerate().take(b_offset) {
+        b_index[index] = match a_variables[..a_offset].iter().position(|a| a == v) {
+            Some(position) if *v == Variable::one() || shared.contains(v) => position,
+            _ => {
+                variables.push(rename(v));
+                variables.len() - 1
+            }
+        };
+    }
+
+    let private_inputs_offset = variables.len();
+
+    // the private variables of `a` are shifted by the public variables added from `b`
+    let shift = private_inputs_offset - a_offset;
+    let a_index = |index: usize| match index < a_offset {
+        true => index,
+        false => index + shift,
+    };
+
+    variables.extend(a_variables[a_offset..].iter().cloned());
+
+    for (index, v) in b_variables.iter().enumerate().skip(b_offset) {
+        b_index[index] = variables.len();
+        variables.push(rename(v));
+    }
+
+    let remap = |l: LinComb<T>, index: &dyn Fn(usize) -> usize| -> LinComb<T> {
+        l.into_iter().map(|(i, c)| (index(i), c)).collect()
+    };
+
+    let constraints = a_constraints
+        .into_iter()
+        .map(|(x, y, z)| (remap(x, &a_index), remap(y, &a_index), remap(z, &a_index)))
+        .chain(b_constraints.into_iter().map(|(x, y, z)| {
+            let index = |i: usize| b_index[i];
+            (remap(x, &index), remap(y, &index), remap(z, &index))
+        }))
+        .collect();
+
+    (variables, private_inputs_offset, constraints)
+}
+
+/// Returns the full assignment vector of a witness, in the column order of the R1CS representation:
+/// the constant `~one` first, then the public inputs, then the private ones.
+///
+/// # Arguments
+///
+/// * `variables` - The variables returned by `r1cs_program`, ordered by index.
+/// * `witness` - The witness to lay out.
+pub fn witness_vector<T: Field>(
+    variables: &[Variable],
+    witness: &Witness<T>,
+) -> std::result::Result<Vec<T>, Error> {
+    variables
+        .iter()
+        .map(|v| match v {
+            v if *v == Variable::one() => Ok(T::one()),
+            v => witness
+                .get(v)
+                .cloned()
+                .ok_or(Error::MissingWitnessValue(*v)),
+        })
+        .collect()
+}
+
+/// Checks that `<A,x>*<B,x> = <C,x>` holds for every row of `constraints`, failing with the index of the first row which does not
+///
+/// # Arguments
+///
+/// * `constraints` - The constraints returned by `r1cs_program`.
+/// * `assignment` - The witness in column order, as returned by `witness_vector`.
+pub fn verify_witness<T: Field>(
+    constraints: &[Constraint<T>],
+    assignment: &[T],
+) -> std::result::Result<(), Error> {
+    let expected = constraints
+        .iter()
+        .flat_map(|(a, b, c)| a.iter().chain(b.iter()).chain(c.iter()))
+        .map(|(i, _)| i + 1)
+        .max()
+        .unwrap_or(0);
+
+    if assignment.len() < expected {
+        return Err(Error::AssignmentLength {
+            expected,
+            found: assignment.len(),
+        });
+    }
+
+    let evaluate = |l: &LinComb<T>| {
+        l.iter()
+            .fold(T::zero(), |acc, (i, c)| acc + assignment[*i].clone() * c)
+    };
+
+    match constraints
+        .iter()
+        .position(|(a, b, c)| evaluate(a) * evaluate(b) != evaluate(c))
+    {
+        Some(row) => Err(Error::UnsatisfiedConstraint(row)),
+        None => Ok(()),
+    }
+}
+
+/// Same as `witness_vector`, but also checks the witness against `constraints` when `debug_check` is set,
+/// so that an inconsistent witness is caught before being handed to an external prover
+///
+/// # Arguments
+///
+/// * `prepared` - The output of `r1cs_program`.
+/// * `witness` - The witness to lay out.
+/// * `debug_check` - Whether to run `verify_witness` on the result.
+#[allow(clippy::type_complexity)]
+pub fn prepare_witness<T: Field>(
+    prepared: &(Vec<Variable>, usize, Vec<Constraint<T>>),
+    witness: &Witness<T>,
+    debug_check: bool,
+) -> std::result::Result<Vec<T>, Error> {
+    let (variables, _, constraints) = prepared;
+
+    let assignment = witness_vector(variables, witness)?;
+
+    if debug_check {
+        verify_witness(constraints, &assignment)?;
+    }
+
+    Ok(assignment)
+}
+
+/// Writes an R1CS representation returned by `r1cs_program` as human-readable text, for debugging small circuits.
+/// A header lists the variables by index and marks the public ones, then each constraint is written on its own line
+/// as `(A) * (B) = (C)`, where each linear combination is a sum of `index * coefficient` terms.
+///
+/// # Arguments
+///
+/// * `variables` - The variables returned by `r1cs_program`, ordered by index.
+/// * `constraints` - The constraints returned by `r1cs_program`.
+/// * `num_public` - The number of public variables, which follow `~one` at index 0.
+/// * `writer` - The writer to write the text to.
+pub fn write_r1cs_text<T: Field, W: Write>(
+    variables: &[Variable],
+    constraints: &[Constraint<T>],
+    num_public: usize,
+    writer: &mut W,
+) -> Result<()> {
+    writeln!(writer, "variables {}", variables.len())?;
+    writeln!(writer, "public {}", num_public)?;
+
+    for (index, variable) in variables.iter().enumerate() {
+        match index {
+            i if i > 0 && i <= num_public => writeln!(writer, "{} {} public", index, variable)?,
+            _ => writeln!(writer, "{} {}", index, variable)?,
+        }
+    }
+
+    let format_lincomb = |l: &LinComb<T>| match l.is_empty() {
+        true => "0".to_string(),
+        false => l
+            .iter()
+            .map(|(index, coefficient)| format!("{} * {}", index, coefficient))
+            .collect::<Vec<_>>()
+            .join(" + "),
+    };
+
+    for (a, b, c) in constraints {
+        writeln!(
+            writer,
+            "({}) * ({}) = ({})",
+            format_lincomb(a),
+            format_lincomb(b),
+            format_lincomb(c)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// One of the matrices of an R1CS representation, such that `<A,x>*<B,x> = <C,x>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Matrix {
+    A,
+    B,
+    C,
+}
+
+/// Writes one matrix of an R1CS representation returned by `r1cs_program` in the MatrixMarket coordinate format.
+/// Rows are constraints and columns are variables, both 1-indexed, and the values are written as decimal integers.
+/// The number of columns is one more than the highest variable index used in any of the three matrices.
+///
+/// # Arguments
+///
+/// * `constraints` - The constraints returned by `r1cs_program`.
+/// * `which` - The matrix to write.
+/// * `writer` - The writer to write the matrix to.
+pub fn write_matrixmarket<T: Field, W: Write>(
+    constraints: &[Constraint<T>],
+    which: Matrix,
+    writer: &mut W,
+) -> Result<()> {
+    let columns = constraints
+        .iter()
+        .flat_map(|(a, b, c)| a.iter().chain(b).chain(c))
+        .map(|(index, _)| index + 1)
+        .max()
+        .unwrap_or(0);
+
+    let matrix: Vec<&LinComb<T>> = constraints
+        .iter()
+        .map(|(a, b, c)| match which {
+            Matrix::A => a,
+            Matrix::B => b,
+            Matrix::C => c,
+        })
+        .collect();
+
+    let entries: usize = matrix.iter().map(|l| l.len()).sum();
+
+    writeln!(writer, "%%MatrixMarket matrix coordinate integer general")?;
+    writeln!(writer, "{} {} {}", constraints.len(), columns, entries)?;
+
+    for (row, l) in matrix.into_iter().enumerate() {
+        for (index, value) in l {
+            writeln!(
+                writer,
+                "{} {} {}",
+                row + 1,
+                index + 1,
+                value.to_dec_string()
+            )?;
+        }
+    }
+
+    Ok(())
 }
 
 pub fn write_r1cs<T: Field, W: Write>(writer: &mut W, p: Prog<T>) -> Result<()> {
+    check_public_inputs(&p)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+
     let modulo_byte_count = T::max_value().to_biguint().add(1u32).to_bytes_le().len() as u32;
 
     let n_pub_out = p.return_count as u32;
@@ -159,7 +769,7 @@ pub fn write_r1cs<T: Field, W: Write>(writer: &mut W, p: Prog<T>) -> Result<()>
     // section type: constraints
     // type
     writer.write_u32::<LittleEndian>(2)?;
-    // size: 4 per lc + (32 + 4) per summand
+    // size: 4 per lc + (modulo_byte_count + 4) per summand
     let size = constraints
         .iter()
         .map(|(a, b, c)| {
@@ -171,13 +781,13 @@ pub fn write_r1cs<T: Field, W: Write>(writer: &mut W, p: Prog<T>) -> Result<()>
         .sum();
     writer.write_u64::<LittleEndian>(size)?;
 
-    write_constraints(writer, constraints)?;
+    write_constraints(writer, constraints, modulo_byte_count as usize)?;
 
     // section type: header
     // type
     writer.write_u32::<LittleEndian>(1)?;
-    // size
-    writer.write_u64::<LittleEndian>(32 + 32)?;
+    // size: 32 bytes for the counts, `modulo_byte_count` bytes for the prime
+    writer.write_u64::<LittleEndian>(32 + modulo_byte_count as u64)?;
 
     // header
     write_header(writer, header)?;
@@ -196,20 +806,25 @@ pub fn write_r1cs<T: Field, W: Write>(writer: &mut W, p: Prog<T>) -> Result<()>
 fn write_constraints<T: Field, W: Write>(
     writer: &mut W,
     constraints: Vec<Constraint<T>>,
+    value_size: usize,
 ) -> Result<()> {
     for c in constraints {
-        write_lincomb(writer, c.0)?;
-        write_lincomb(writer, c.1)?;
-        write_lincomb(writer, c.2)?;
+        write_lincomb(writer, c.0, value_size)?;
+        write_lincomb(writer, c.1, value_size)?;
+        write_lincomb(writer, c.2, value_size)?;
     }
     Ok(())
 }
 
-fn write_lincomb<T: Field, W: Write>(writer: &mut W, l: LinComb<T>) -> Result<()> {
+fn write_lincomb<T: Field, W: Write>(
+    writer: &mut W,
+    l: LinComb<T>,
+    value_size: usize,
+) -> Result<()> {
     writer.write_u32::<LittleEndian>(l.len() as u32)?;
     for (var, coeff) in l {
         writer.write_u32::<LittleEndian>(var as u32)?;
-        let mut res = vec![0u8; 32];
+        let mut res = vec![0u8; value_size];
         for (value, padded) in coeff.to_biguint().to_bytes_le().iter().zip(res.iter_mut()) {
             *padded = *value;
         }
@@ -235,10 +850,609 @@ mod tests {
     use zkutil::r1cs_reader;
     use zokrates_ast::{
         flat::{Parameter, Variable},
-        ir::{LinComb, QuadComb, Statement},
+        ir::{Directive, LinComb, QuadComb, Statement},
     };
     use zokrates_field::Bn128Field;
 
+    #[test]
+    fn single_multiplication_profile() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::private(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![Statement::Constraint(
+                QuadComb::from_linear_combinations(
+                    Variable::new(0).into(),
+                    Variable::new(1).into(),
+                ),
+                Variable::public(0).into(),
+                None,
+            )],
+        };
+
+        assert_eq!(constraints_per_statement(&prog), vec![(0, 1)]);
+        assert_eq!(r1cs_program(prog).2.len(), 1);
+    }
+
+    #[test]
+    fn constraint_limit() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            return_count: 1,
+            statements: vec![
+                Statement::Constraint(LinComb::one().into(), Variable::public(0).into(), None),
+                Statement::Constraint(LinComb::one().into(), Variable::public(0).into(), None),
+            ],
+        };
+
+        assert_eq!(
+            r1cs_program_with_limit(prog.clone(), Some(1)),
+            Err(Error::TooManyConstraints { limit: 1, found: 2 })
+        );
+        assert_eq!(
+            r1cs_program_with_limit(prog.clone(), Some(2)),
+            Ok(r1cs_program(prog.clone()))
+        );
+        assert_eq!(
+            r1cs_program_with_limit(prog.clone(), None),
+            Ok(r1cs_program(prog))
+        );
+    }
+
+    #[test]
+    fn duplicate_public_input() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::public(Variable::new(0)),
+                Parameter::private(Variable::new(1)),
+                Parameter::public(Variable::new(0)),
+            ],
+            return_count: 0,
+            statements: vec![],
+        };
+
+        assert_eq!(
+            check_public_inputs(&prog),
+            Err(Error::DuplicatePublicInput(Variable::new(0)))
+        );
+        assert_eq!(check_public_inputs(&Prog::<Bn128Field>::default()), Ok(()));
+
+        let mut buf = Vec::new();
+        let err = write_r1cs(&mut buf, prog).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn deterministic_indices() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::public(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        Variable::new(0).into(),
+                        Variable::new(1).into(),
+                    ),
+                    Variable::new(2).into(),
+                    None,
+                ),
+                Statement::Constraint(
+                    (LinComb::from(Variable::new(2)) + LinComb::from(Variable::new(0))).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let (variables, private_inputs_offset, constraints) = r1cs_program(prog.clone());
+
+        // each variable is assigned exactly one index
+        assert_eq!(
+            variables.iter().collect::<BTreeSet<_>>().len(),
+            variables.len()
+        );
+
+        assert_eq!(
+            r1cs_program(prog),
+            (variables, private_inputs_offset, constraints)
+        );
+    }
+
+    #[test]
+    fn bit_decomposition_hints() {
+        let bit = |v: Variable| {
+            Statement::Constraint(
+                QuadComb::from_linear_combinations(v.into(), v.into()),
+                v.into(),
+                None,
+            )
+        };
+
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![Parameter::private(Variable::new(0))],
+            return_count: 0,
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(0).into()],
+                    outputs: vec![Variable::new(1), Variable::new(2)],
+                    solver: Solver::Bits(2),
+                }),
+                bit(Variable::new(1)),
+                bit(Variable::new(2)),
+                Statement::Constraint(
+                    (LinComb::summand(2, Variable::new(1)) + LinComb::from(Variable::new(2)))
+                        .into(),
+                    Variable::new(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let (variables, _, _, hints) = r1cs_program_with_hints(prog);
+
+        let index = |v: Variable| variables.iter().position(|w| *w == v).unwrap();
+
+        assert_eq!(hints.len(), 3);
+        assert_eq!(hints[&index(Variable::new(1))], VariableHint::Boolean);
+        assert_eq!(hints[&index(Variable::new(2))], VariableHint::Boolean);
+        assert_eq!(
+            hints[&index(Variable::new(0))],
+            VariableHint::RangeBounded(2)
+        );
+    }
+
+    #[test]
+    fn no_hint_for_repeated_powers() {
+        let bit = |v: Variable| {
+            Statement::Constraint(
+                QuadComb::from_linear_combinations(v.into(), v.into()),
+                v.into(),
+                None,
+            )
+        };
+
+        // b1 + b2 == v can be 2, which does not fit in one bit
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![Parameter::private(Variable::new(0))],
+            return_count: 0,
+            statements: vec![
+                bit(Variable::new(1)),
+                bit(Variable::new(2)),
+                Statement::Constraint(
+                    (LinComb::from(Variable::new(1)) + LinComb::from(Variable::new(2))).into(),
+                    Variable::new(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let (variables, _, _, hints) = r1cs_program_with_hints(prog);
+
+        let index = |v: Variable| variables.iter().position(|w| *w == v).unwrap();
+
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[&index(Variable::new(1))], VariableHint::Boolean);
+        assert_eq!(hints[&index(Variable::new(2))], VariableHint::Boolean);
+        assert!(!hints.contains_key(&index(Variable::new(0))));
+    }
+
+    #[test]
+    fn witness_in_column_order() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::public(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![Statement::Constraint(
+                (LinComb::from(Variable::new(0)) + LinComb::from(Variable::new(1))).into(),
+                Variable::public(0).into(),
+                None,
+            )],
+        };
+
+        let (variables, _, _) = r1cs_program(prog);
+
+        let witness = Witness::from_map(
+            vec![
+                (Variable::new(0), Bn128Field::from(2)),
+                (Variable::new(1), Bn128Field::from(3)),
+                (Variable::public(0), Bn128Field::from(5)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        // columns are `[~one, ~out_0, _1, _0]`
+        assert_eq!(
+            witness_vector(&variables, &witness),
+            Ok(vec![
+                Bn128Field::from(1),
+                Bn128Field::from(5),
+                Bn128Field::from(3),
+                Bn128Field::from(2)
+            ])
+        );
+
+        assert_eq!(
+            witness_vector(&variables, &Witness::empty()),
+            Err(Error::MissingWitnessValue(Variable::public(0)))
+        );
+    }
+
+    #[test]
+    fn compose() {
+        // _0 * _0 == _1 with a public input _0
+        let prog = || -> Prog<Bn128Field> {
+            Prog {
+                arguments: vec![Parameter::public(Variable::new(0))],
+                return_count: 0,
+                statements: vec![Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::from(Variable::new(0)),
+                        LinComb::from(Variable::new(0)),
+                    ),
+                    LinComb::from(Variable::new(1)),
+                    None,
+                )],
+            }
+        };
+
+        let one = || Bn128Field::from(1);
+
+        // sharing the public input `_0`, `_1` in the second circuit becomes `_2`
+        let (variables, private_inputs_offset, constraints) = compose_r1cs(
+            r1cs_program(prog()),
+            r1cs_program(prog()),
+            &[Variable::new(0)],
+        );
+
+        assert_eq!(
+            variables,
+            vec![
+                Variable::one(),
+                Variable::new(0),
+                Variable::new(1),
+                Variable::new(2)
+            ]
+        );
+        assert_eq!(private_inputs_offset, 2);
+        assert_eq!(
+            constraints,
+            vec![
+                (vec![(1, one())], vec![(1, one())], vec![(2, one())]),
+                (vec![(1, one())], vec![(1, one())], vec![(3, one())]),
+            ]
+        );
+
+        // without sharing, the public input of the second circuit becomes `_2` and `_1` becomes `_3`
+        let (variables, private_inputs_offset, constraints) =
+            compose_r1cs(r1cs_program(prog()), r1cs_program(prog()), &[]);
+
+        assert_eq!(
+            variables,
+            vec![
+                Variable::one(),
+                Variable::new(0),
+                Variable::new(2),
+                Variable::new(1),
+                Variable::new(3)
+            ]
+        );
+        assert_eq!(private_inputs_offset, 3);
+        assert_eq!(
+            constraints,
+            vec![
+                (vec![(1, one())], vec![(1, one())], vec![(3, one())]),
+                (vec![(2, one())], vec![(2, one())], vec![(4, one())]),
+            ]
+        );
+    }
+
+    #[test]
+    fn counting_sink() {
+        #[derive(Default)]
+        struct CountingSink {
+            a: usize,
+            b: usize,
+            c: usize,
+        }
+
+        impl<T> R1csSink<T> for CountingSink {
+            type Output = (usize, usize, usize);
+
+            fn push_a(&mut self, _: usize, _: usize, _: T) {
+                self.a += 1;
+            }
+
+            fn push_b(&mut self, _: usize, _: usize, _: T) {
+                self.b += 1;
+            }
+
+            fn push_c(&mut self, _: usize, _: usize, _: T) {
+                self.c += 1;
+            }
+
+            fn finish(self) -> Self::Output {
+                (self.a, self.b, self.c)
+            }
+        }
+
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::public(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::from(Variable::new(0)),
+                        LinComb::from(Variable::new(0)),
+                    ),
+                    LinComb::from(Variable::new(0)),
+                    None,
+                ),
+                Statement::Constraint(
+                    (LinComb::from(Variable::new(0)) + LinComb::from(Variable::new(1))).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        // A has one term per constraint, B has one then two, C has one per constraint
+        let (_, _, counts) = r1cs_program_to_sink(prog, CountingSink::default());
+
+        assert_eq!(counts, (2, 3, 2));
+    }
+
+    #[test]
+    fn text() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::public(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::from(Variable::new(0)),
+                        LinComb::from(Variable::new(0)),
+                    ),
+                    LinComb::from(Variable::new(0)),
+                    None,
+                ),
+                Statement::Constraint(
+                    (LinComb::from(Variable::new(0)) + LinComb::from(Variable::new(1))).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let (variables, private_inputs_offset, constraints) = r1cs_program(prog);
+
+        let mut buf = Vec::new();
+        write_r1cs_text(
+            &variables,
+            &constraints,
+            private_inputs_offset - 1,
+            &mut buf,
+        )
+        .unwrap();
+
+        let expected = "variables 4
+public 2
+0 ~one
+1 ~out_0 public
+2 _1 public
+3 _0
+(3 * 1) * (3 * 1) = (3 * 1)
+(0 * 1) * (3 * 1 + 2 * 1) = (1 * 1)
+";
+
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn matrixmarket() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::public(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::from(Variable::new(0)),
+                        LinComb::from(Variable::new(0)),
+                    ),
+                    LinComb::from(Variable::new(0)),
+                    None,
+                ),
+                Statement::Constraint(
+                    (LinComb::from(Variable::new(0)) + LinComb::summand(2, Variable::new(1)))
+                        .into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let (_, _, constraints) = r1cs_program(prog);
+
+        let mut buf = Vec::new();
+        write_matrixmarket(&constraints, Matrix::A, &mut buf).unwrap();
+
+        // 2 constraints, 4 variables and 2 non-zero entries in A
+        let expected = "%%MatrixMarket matrix coordinate integer general
+2 4 2
+1 4 1
+2 1 1
+";
+
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn reject_inconsistent_witness() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![Parameter::private(Variable::new(0))],
+            return_count: 1,
+            statements: vec![
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::from(Variable::new(0)),
+                        LinComb::from(Variable::new(0)),
+                    ),
+                    LinComb::from(Variable::new(1)),
+                    None,
+                ),
+                Statement::Constraint(
+                    LinComb::from(Variable::new(1)).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let prepared = r1cs_program(prog);
+
+        let witness = |out: u32| {
+            Witness::from_map(
+                vec![
+                    (Variable::new(0), Bn128Field::from(3)),
+                    (Variable::new(1), Bn128Field::from(9)),
+                    (Variable::public(0), Bn128Field::from(out)),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        };
+
+        assert!(prepare_witness(&prepared, &witness(9), true).is_ok());
+        assert_eq!(
+            prepare_witness(&prepared, &witness(10), true),
+            Err(Error::UnsatisfiedConstraint(1))
+        );
+        assert!(prepare_witness(&prepared, &witness(10), false).is_ok());
+
+        let assignment = prepare_witness(&prepared, &witness(9), false).unwrap();
+        assert_eq!(
+            verify_witness(&prepared.2, &assignment[..2]),
+            Err(Error::AssignmentLength {
+                expected: assignment.len(),
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn reorder_two_public_inputs() {
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::public(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![Statement::Constraint(
+                (LinComb::from(Variable::new(0)) + LinComb::from(Variable::new(1))).into(),
+                Variable::public(0).into(),
+                None,
+            )],
+        };
+
+        let prepared = r1cs_program(prog);
+
+        // the public part is `[~out_0, _1]`
+        assert_eq!(
+            &prepared.0[..3],
+            &[Variable::one(), Variable::public(0), Variable::new(1)]
+        );
+
+        assert_eq!(
+            reorder_public_inputs(prepared.clone(), &[0]),
+            Err(Error::PermutationLength {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            reorder_public_inputs(prepared.clone(), &[1, 1]),
+            Err(Error::PermutationIndex(1))
+        );
+
+        let (variables, private_inputs_offset, constraints) =
+            reorder_public_inputs(prepared, &[1, 0]).unwrap();
+
+        assert_eq!(
+            variables,
+            vec![
+                Variable::one(),
+                Variable::new(1),
+                Variable::public(0),
+                Variable::new(0)
+            ]
+        );
+        assert_eq!(private_inputs_offset, 3);
+        assert_eq!(
+            constraints,
+            vec![(
+                vec![(0, Bn128Field::from(1))],
+                vec![(3, Bn128Field::from(1)), (1, Bn128Field::from(1))],
+                vec![(2, Bn128Field::from(1))]
+            )]
+        );
+    }
+
+    #[test]
+    fn wide_field() {
+        use zokrates_field::Bw6_761Field;
+
+        let prog: Prog<Bw6_761Field> = Prog {
+            arguments: vec![],
+            return_count: 1,
+            statements: vec![Statement::Constraint(
+                LinComb::one().into(),
+                Variable::public(0).into(),
+                None,
+            )],
+        };
+
+        let mut buf = Vec::new();
+        write_r1cs(&mut buf, prog).unwrap();
+
+        // the scalar field of bw6_761 takes 48 bytes
+        let value_size = 48;
+
+        // 3 lcs with a single summand each
+        let constraints_size = 3 * (4 + 4 + value_size);
+        assert_eq!(buf[16..24], (constraints_size as u64).to_le_bytes());
+
+        let header_start = 24 + constraints_size;
+        assert_eq!(buf[header_start..header_start + 4], 1u32.to_le_bytes());
+        assert_eq!(
+            buf[header_start + 4..header_start + 12],
+            (32 + value_size as u64).to_le_bytes()
+        );
+        assert_eq!(
+            buf[header_start + 12..header_start + 16],
+            (value_size as u32).to_le_bytes()
+        );
+
+        // header, then a wire map of two wires
+        assert_eq!(buf.len(), header_start + 12 + 32 + value_size + 12 + 2 * 8);
+    }
+
     #[test]
     fn empty() {
         let prog: Prog<Bn128Field> = Prog::default();
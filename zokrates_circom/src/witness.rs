@@ -1,9 +1,11 @@
 use std::{
-    io::{Result, Write},
+    collections::HashMap,
+    io::{Error, ErrorKind, Read, Result, Write},
     ops::Add,
 };
 
-use byteorder::{LittleEndian, WriteBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use num_bigint::BigUint;
 use zokrates_ast::{
     flat::Variable,
     ir::{PublicInputs, Witness},
@@ -103,12 +105,118 @@ fn write_witness_values<T: Field, W: Write>(
     Ok(())
 }
 
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Read a witness in the snarkjs `.wtns` format, assigning the values in order to `variables`
+///
+/// The prime declared in the header must be the modulus of `T`, and the file must hold exactly one value per variable
+pub fn read_witness<T: Field, R: Read>(
+    reader: &mut R,
+    variables: &[Variable],
+) -> Result<Witness<T>> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != [0x77, 0x74, 0x6e, 0x73] {
+        return Err(invalid_data(
+            "Invalid magic number, expected `wtns`".to_string(),
+        ));
+    }
+
+    let version = reader.read_u32::<LittleEndian>()?;
+    if version != 2 {
+        return Err(invalid_data(format!(
+            "Unsupported wtns version {}",
+            version
+        )));
+    }
+
+    // sections can appear in any order, so collect them before interpreting them
+    let section_count = reader.read_u32::<LittleEndian>()?;
+    let mut sections = HashMap::new();
+    for _ in 0..section_count {
+        let section_type = reader.read_u32::<LittleEndian>()?;
+        let size = reader.read_u64::<LittleEndian>()?;
+        // the size comes from the input, so only allocate for the bytes actually read
+        let mut section = vec![];
+        reader.by_ref().take(size).read_to_end(&mut section)?;
+        if (section.len() as u64) < size {
+            return Err(invalid_data(format!(
+                "Section {} is truncated: expected {} bytes, found {}",
+                section_type,
+                size,
+                section.len()
+            )));
+        }
+        sections.insert(section_type, section);
+    }
+
+    let mut header = sections
+        .get(&1)
+        .ok_or_else(|| invalid_data("Missing header section".to_string()))?
+        .as_slice();
+
+    let field_size = header.read_u32::<LittleEndian>()? as usize;
+    if field_size > header.len() {
+        return Err(invalid_data(format!(
+            "Header declares a field size of {} bytes, but only {} remain",
+            field_size,
+            header.len()
+        )));
+    }
+    let mut prime = vec![0u8; field_size];
+    header.read_exact(&mut prime)?;
+    let witness_size = header.read_u32::<LittleEndian>()? as usize;
+
+    let modulus = T::max_value().to_biguint().add(1u32);
+    if BigUint::from_bytes_le(&prime) != modulus {
+        return Err(invalid_data(format!(
+            "Witness is defined over the prime {}, expected {} for curve {}",
+            BigUint::from_bytes_le(&prime),
+            modulus,
+            T::name()
+        )));
+    }
+
+    if witness_size != variables.len() {
+        return Err(invalid_data(format!(
+            "Witness has {} values, expected {}",
+            witness_size,
+            variables.len()
+        )));
+    }
+
+    let values = sections
+        .get(&2)
+        .ok_or_else(|| invalid_data("Missing witness section".to_string()))?;
+
+    if values.len() != witness_size * field_size {
+        return Err(invalid_data(format!(
+            "Witness section has {} bytes, expected {}",
+            values.len(),
+            witness_size * field_size
+        )));
+    }
+
+    let values = values
+        .chunks(field_size)
+        .map(|bytes| {
+            let value = BigUint::from_bytes_le(bytes);
+            T::try_from(value.clone())
+                .map_err(|_| invalid_data(format!("Value {} is not in the field", value)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Witness(variables.iter().cloned().zip(values).collect()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
     use zokrates_ast::{flat::Variable, ir::PublicInputs};
-    use zokrates_field::Bn128Field;
+    use zokrates_field::{Bls12_381Field, Bn128Field};
 
     #[test]
     fn empty() {
@@ -216,4 +324,65 @@ mod tests {
 
         assert_eq!(buf, expected);
     }
+
+    #[test]
+    fn read() {
+        let mut w: Witness<Bn128Field> = Witness::default();
+        let public_inputs: PublicInputs = vec![Variable::new(1)].into_iter().collect();
+        w.0.extend(vec![
+            (Variable::public(0), 42.into()),
+            (Variable::one(), 1.into()),
+            (Variable::new(0), 43.into()),
+            (Variable::new(1), 44.into()),
+        ]);
+        let mut buf = Vec::new();
+
+        write_witness(&mut buf, w.clone(), public_inputs).unwrap();
+
+        // values are written in the order [one, ~out_0, _1, _0]
+        let variables = vec![
+            Variable::one(),
+            Variable::public(0),
+            Variable::new(1),
+            Variable::new(0),
+        ];
+
+        assert_eq!(
+            read_witness::<Bn128Field, _>(&mut buf.as_slice(), &variables).unwrap(),
+            w
+        );
+    }
+
+    #[test]
+    fn read_wrong_prime() {
+        let mut buf = Vec::new();
+
+        write_witness(
+            &mut buf,
+            Witness::<Bn128Field>::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        assert!(read_witness::<Bls12_381Field, _>(&mut buf.as_slice(), &[]).is_err());
+    }
+
+    #[test]
+    fn read_truncated_section() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&[0x77, 0x74, 0x6e, 0x73]);
+        buf.write_u32::<LittleEndian>(2).unwrap();
+        buf.write_u32::<LittleEndian>(1).unwrap();
+        // a header section claiming far more bytes than the input holds
+        buf.write_u32::<LittleEndian>(1).unwrap();
+        buf.write_u64::<LittleEndian>(u64::MAX).unwrap();
+        buf.write_u32::<LittleEndian>(32).unwrap();
+
+        assert_eq!(
+            read_witness::<Bn128Field, _>(&mut buf.as_slice(), &[])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+    }
 }
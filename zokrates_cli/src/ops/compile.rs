@@ -156,7 +156,8 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let program_flattened = program_flattened.collect();
 
-    write_r1cs(&mut r1cs_writer, program_flattened.clone()).unwrap();
+    write_r1cs(&mut r1cs_writer, program_flattened.clone())
+        .map_err(|why| format!("Could not write {}: {}", r1cs_output_path.display(), why))?;
 
     match program_flattened.serialize(&mut bin_writer) {
         Ok(constraint_count) => {
@@ -117,7 +117,7 @@ fn cli_compute<'a, T: Field, I: Iterator<Item = ir::Statement<'a, T>>>(
             ))),
     };
 
-    use zokrates_abi::Inputs;
+    use zokrates_abi::{parse_public_inputs, Inputs};
 
     // get arguments
     let arguments = match is_stdin {
@@ -126,8 +126,8 @@ fn cli_compute<'a, T: Field, I: Iterator<Item = ir::Statement<'a, T>>>(
             let arguments = sub_matches.values_of("arguments");
             arguments
                 .map(|a| {
-                    a.map(|x| T::try_from_dec_str(x).map_err(|_| x.to_string()))
-                        .collect::<Result<Vec<_>, _>>()
+                    parse_public_inputs(&a.map(|x| x.to_string()).collect::<Vec<_>>())
+                        .map_err(|e| e.to_string())
                 })
                 .unwrap_or_else(|| Ok(vec![]))
                 .map(Inputs::Raw)
@@ -153,11 +153,11 @@ fn cli_compute<'a, T: Field, I: Iterator<Item = ir::Statement<'a, T>>>(
                     _ => match stdin.read_to_string(&mut input) {
                         Ok(_) => {
                             input.retain(|x| x != '\n');
-                            input
-                                .split(' ')
-                                .map(|x| T::try_from_dec_str(x).map_err(|_| x.to_string()))
-                                .collect::<Result<Vec<_>, _>>()
-                                .map(Inputs::Raw)
+                            parse_public_inputs(
+                                &input.split(' ').map(|x| x.to_string()).collect::<Vec<_>>(),
+                            )
+                            .map(Inputs::Raw)
+                            .map_err(|e| e.to_string())
                         }
                         Err(_) => Err(String::from("???")),
                     },
@@ -172,7 +172,7 @@ fn cli_compute<'a, T: Field, I: Iterator<Item = ir::Statement<'a, T>>>(
     let public_inputs = ir_prog.public_inputs();
 
     let witness = interpreter
-        .execute_with_log_stream(ir_prog, &arguments.encode(), &mut std::io::stdout())
+        .execute_parallel(ir_prog, &arguments.encode(), &mut std::io::stdout())
         .map_err(|e| format!("Execution failed: {}", e))?;
 
     use zokrates_abi::Decode;
@@ -2217,9 +2217,27 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                     _ => panic!("Expected number as pow exponent"),
                 }
             }
-            FieldElementExpression::Conditional(e) => self
-                .flatten_conditional_expression(statements_flattened, e)
-                .get_field_unchecked(),
+            FieldElementExpression::Conditional(e) => {
+                // `if c { 1 } else { 0 }` is `c` cast to a field element, and `if c { 0 } else { 1 }` is `!c`
+                match (e.consequence.as_ref(), e.alternative.as_ref()) {
+                    (FieldElementExpression::Number(c), FieldElementExpression::Number(a))
+                        if *c == T::one() && *a == T::zero() =>
+                    {
+                        self.flatten_boolean_expression(statements_flattened, *e.condition)
+                    }
+                    (FieldElementExpression::Number(c), FieldElementExpression::Number(a))
+                        if *c == T::zero() && *a == T::one() =>
+                    {
+                        FlatExpression::Sub(
+                            box FlatExpression::Number(T::one()),
+                            box self.flatten_boolean_expression(statements_flattened, *e.condition),
+                        )
+                    }
+                    _ => self
+                        .flatten_conditional_expression(statements_flattened, e)
+                        .get_field_unchecked(),
+                }
+            }
             _ => unreachable!(),
         }
     }
@@ -3606,6 +3624,52 @@ mod tests {
         flattener.flatten_field_expression(&mut FlatStatements::new(), expression);
     }
 
+    #[test]
+    fn if_else_one_zero() {
+        let config = CompileConfig::default();
+        let mut flattener = Flattener::new(config);
+        let mut statements_flattened = FlatStatements::new();
+
+        let definition = ZirStatement::Definition(
+            zir::Variable::boolean("b".into()),
+            BooleanExpression::Value(true).into(),
+        );
+
+        flattener.flatten_statement(&mut statements_flattened, definition);
+
+        let b = Variable::new(0);
+
+        // if b { 1 } else { 0 }
+        let expression = FieldElementExpression::conditional(
+            BooleanExpression::identifier("b".into()),
+            FieldElementExpression::Number(Bn128Field::from(1)),
+            FieldElementExpression::Number(Bn128Field::from(0)),
+        );
+
+        assert_eq!(
+            flattener.flatten_field_expression(&mut statements_flattened, expression),
+            FlatExpression::Identifier(b)
+        );
+
+        // if b { 0 } else { 1 }
+        let expression = FieldElementExpression::conditional(
+            BooleanExpression::identifier("b".into()),
+            FieldElementExpression::Number(Bn128Field::from(0)),
+            FieldElementExpression::Number(Bn128Field::from(1)),
+        );
+
+        assert_eq!(
+            flattener.flatten_field_expression(&mut statements_flattened, expression),
+            FlatExpression::Sub(
+                box FlatExpression::Number(Bn128Field::from(1)),
+                box FlatExpression::Identifier(b)
+            )
+        );
+
+        // no statements were added apart from the definition of `b`
+        assert_eq!(statements_flattened.len(), 1);
+    }
+
     #[test]
     fn geq_leq() {
         let config = CompileConfig::default();
@@ -18,6 +18,8 @@ pub struct CompileConfig {
     pub isolate_branches: bool,
     #[serde(default)]
     pub debug: bool,
+    #[serde(default)]
+    pub directive_cache_size: Option<usize>,
 }
 
 impl CompileConfig {
@@ -30,4 +32,9 @@ impl CompileConfig {
         self.debug = debug;
         self
     }
+
+    pub fn directive_cache_size(mut self, size: Option<usize>) -> Self {
+        self.directive_cache_size = size;
+        self
+    }
 }
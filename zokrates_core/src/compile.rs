@@ -197,7 +197,7 @@ pub fn compile<'ast, T: Field, E: Into<imports::Error>>(
 
     // optimize
     log::debug!("Optimise IR");
-    let optimized_ir_prog = optimize(ir_prog);
+    let optimized_ir_prog = optimize(ir_prog, config.directive_cache_size);
 
     // clean (remove blocks)
     let clean_ir_prog = optimized_ir_prog.clean();
@@ -351,6 +351,41 @@ mod test {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn assertion_error_code() {
+        let source = r#"
+            def main(field a) {
+                assert(a == 42, "[E_ANSWER] wrong answer");
+                return;
+            }
+        "#
+        .to_string();
+
+        let arena = Arena::new();
+        let artifacts: CompilationArtifacts<Bn128Field, _> = compile(
+            source,
+            "main".into(),
+            None::<&dyn Resolver<io::Error>>,
+            CompileConfig::default(),
+            &arena,
+        )
+        .unwrap();
+
+        let errors: Vec<_> = artifacts
+            .prog()
+            .statements
+            .into_iter()
+            .filter_map(|s| match s {
+                ir::Statement::Constraint(_, _, Some(e)) => Some(e.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("[E_ANSWER]: \"wrong answer\"")));
+    }
+
     mod abi {
         use super::*;
         use zokrates_ast::typed::abi::*;
@@ -5,3 +5,5 @@ pub mod imports;
 mod macros;
 mod optimizer;
 mod semantics;
+
+pub use optimizer::{run_pass, OptimizationReport};
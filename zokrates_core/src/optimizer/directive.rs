@@ -8,8 +8,15 @@
 // b := Directive(a)
 // c := b
 // ```
+// Identity directives `b := Identity(a)` are removed and `b` is replaced by `a`
+//
+// When bounded, only the most recently seen calls are remembered, so that memory stays bounded
+// on programs with mostly unique directives, at the cost of missing some duplicates
 
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use zokrates_ast::common::VariableMap;
 use zokrates_ast::ir::folder::*;
 use zokrates_ast::ir::*;
 use zokrates_field::Field;
@@ -18,9 +25,30 @@ type SolverCall<'ast, T> = (Solver<'ast, T>, Vec<QuadComb<T>>);
 
 #[derive(Debug, Default)]
 pub struct DirectiveOptimizer<'ast, T> {
-    calls: HashMap<SolverCall<'ast, T>, Vec<Variable>>,
+    /// Map of the calls seen so far to their outputs and the time they were last seen at.
+    calls: HashMap<Rc<SolverCall<'ast, T>>, (Vec<Variable>, usize)>,
     /// Map of renamings for reassigned variables while processing the program.
-    substitution: HashMap<Variable, Variable>,
+    substitution: VariableMap<Variable>,
+    /// The maximum number of calls to remember, if any.
+    capacity: Option<usize>,
+    /// The calls remembered when bounded, indexed by the time they were last seen at.
+    /// They share their keys with `calls`.
+    recency: BTreeMap<usize, Rc<SolverCall<'ast, T>>>,
+    time: usize,
+}
+
+impl<'ast, T: Field> DirectiveOptimizer<'ast, T> {
+    /// Create an optimizer which remembers at most `capacity` calls, evicting the least recently seen ones.
+    /// A capacity of zero disables the removal of duplicate calls, identity directives are still removed
+    pub fn bounded(capacity: usize) -> Self {
+        DirectiveOptimizer {
+            calls: HashMap::default(),
+            substitution: VariableMap::default(),
+            capacity: Some(capacity),
+            recency: BTreeMap::default(),
+            time: 0,
+        }
+    }
 }
 
 impl<'ast, T: Field> Folder<'ast, T> for DirectiveOptimizer<'ast, T> {
@@ -33,14 +61,53 @@ impl<'ast, T: Field> Folder<'ast, T> for DirectiveOptimizer<'ast, T> {
             Statement::Directive(d) => {
                 let d = self.fold_directive(d);
 
-                match self.calls.entry((d.solver.clone(), d.inputs.clone())) {
+                if d.solver.is_identity() {
+                    if let Some((input, coefficient)) = d.inputs[0]
+                        .clone()
+                        .try_linear()
+                        .ok()
+                        .and_then(|l| l.try_summand().ok())
+                    {
+                        if coefficient == T::one() {
+                            self.substitution.insert(d.outputs[0], input);
+                            return vec![];
+                        }
+                    }
+                }
+
+                self.time += 1;
+
+                match self
+                    .calls
+                    .entry(Rc::new((d.solver.clone(), d.inputs.clone())))
+                {
                     Entry::Vacant(e) => {
-                        e.insert(d.outputs.clone());
+                        if self.capacity.is_some() {
+                            self.recency.insert(self.time, Rc::clone(e.key()));
+                        }
+                        e.insert((d.outputs.clone(), self.time));
+
+                        if let Some(capacity) = self.capacity {
+                            if self.recency.len() > capacity {
+                                let oldest = *self.recency.keys().next().unwrap();
+                                let evicted = self.recency.remove(&oldest).unwrap();
+                                self.calls.remove(&evicted);
+                            }
+                        }
+
                         vec![Statement::Directive(d)]
                     }
-                    Entry::Occupied(e) => {
+                    Entry::Occupied(mut e) => {
+                        let (outputs, last_seen) = e.get_mut();
+
+                        if self.capacity.is_some() {
+                            let call = self.recency.remove(last_seen).unwrap();
+                            self.recency.insert(self.time, call);
+                        }
+                        *last_seen = self.time;
+
                         self.substitution
-                            .extend(d.outputs.into_iter().zip(e.get().iter().cloned()));
+                            .extend(d.outputs.into_iter().zip(outputs.iter().cloned()));
                         vec![]
                     }
                 }
@@ -51,4 +118,172 @@ impl<'ast, T: Field> Folder<'ast, T> for DirectiveOptimizer<'ast, T> {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::optimizer::run_pass;
+    use zokrates_ast::common::Solver;
+    use zokrates_ast::zir::{
+        self,
+        types::{Signature, Type},
+        BooleanExpression, FieldElementExpression, Id, ZirFunction, ZirStatement,
+    };
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn remove_identity_directive() {
+        // def identity(field a) -> field { return a; }
+        let identity = ZirFunction {
+            arguments: vec![zir::Parameter {
+                id: zir::Variable::field_element("a"),
+                private: true,
+            }],
+            statements: vec![ZirStatement::Return(vec![
+                FieldElementExpression::identifier("a".into()).into(),
+            ])],
+            signature: Signature {
+                inputs: vec![Type::FieldElement],
+                outputs: vec![Type::FieldElement],
+            },
+        };
+
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![LinComb::from(Variable::new(0)).into()],
+                    outputs: vec![Variable::new(1)],
+                    solver: Solver::Zir(identity),
+                }),
+                Statement::constraint(Variable::new(1), Variable::public(0)),
+            ],
+            return_count: 1,
+        };
+
+        let expected = vec![Statement::constraint(Variable::new(0), Variable::public(0))];
+
+        assert_eq!(
+            DirectiveOptimizer::default().fold_program(p).statements,
+            expected
+        );
+    }
+
+    #[test]
+    fn keep_boolean_identity_directive() {
+        // def identity(bool a) -> bool { return a; }
+        // solving it checks that the input is a boolean, so it must be kept
+        let identity = ZirFunction {
+            arguments: vec![zir::Parameter {
+                id: zir::Variable::boolean("a".into()),
+                private: true,
+            }],
+            statements: vec![ZirStatement::Return(vec![BooleanExpression::identifier(
+                "a".into(),
+            )
+            .into()])],
+            signature: Signature {
+                inputs: vec![Type::Boolean],
+                outputs: vec![Type::Boolean],
+            },
+        };
+
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![LinComb::from(Variable::new(0)).into()],
+                    outputs: vec![Variable::new(1)],
+                    solver: Solver::Zir(identity),
+                }),
+                Statement::constraint(Variable::new(1), Variable::public(0)),
+            ],
+            return_count: 1,
+        };
+
+        assert_eq!(
+            DirectiveOptimizer::default()
+                .fold_program(p.clone())
+                .statements,
+            p.statements
+        );
+    }
+
+    #[test]
+    fn report_duplicate_directive() {
+        let directive = |output| {
+            Statement::Directive(Directive {
+                inputs: vec![LinComb::from(Variable::new(0)).into()],
+                outputs: vec![Variable::new(output)],
+                solver: Solver::Bits(8),
+            })
+        };
+
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![
+                directive(1),
+                directive(2),
+                Statement::constraint(Variable::new(2), Variable::public(0)),
+            ],
+            return_count: 1,
+        };
+
+        let (optimized, report) = run_pass(&mut DirectiveOptimizer::default(), p);
+
+        assert_eq!(
+            optimized.statements,
+            vec![
+                directive(1),
+                Statement::constraint(Variable::new(1), Variable::public(0)),
+            ]
+        );
+        assert_eq!(report.statements_removed(), 1);
+        assert_eq!(report.constraints_before, report.constraints_after);
+        assert_eq!(report.variables_before, 5);
+        assert_eq!(report.variables_after, 4);
+    }
+
+    #[test]
+    fn bounded_evicts_least_recently_seen() {
+        let directive = |input, output| {
+            Statement::Directive(Directive {
+                inputs: vec![LinComb::from(Variable::new(input)).into()],
+                outputs: vec![Variable::new(output)],
+                solver: Solver::Bits(8),
+            })
+        };
+
+        // the duplicate of the first directive comes after a distinct one, which evicts it
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![directive(0, 1), directive(2, 3), directive(0, 4)],
+            return_count: 0,
+        };
+
+        assert_eq!(
+            DirectiveOptimizer::bounded(1)
+                .fold_program(p.clone())
+                .statements,
+            p.statements
+        );
+
+        // without a bound, the duplicate is removed
+        assert_eq!(
+            DirectiveOptimizer::default().fold_program(p).statements,
+            vec![directive(0, 1), directive(2, 3)]
+        );
+
+        // with no room at all, even an immediate duplicate is kept
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![directive(0, 1), directive(0, 2)],
+            return_count: 0,
+        };
+
+        assert_eq!(
+            DirectiveOptimizer::bounded(0)
+                .fold_program(p.clone())
+                .statements,
+            p.statements
+        );
+    }
+}
@@ -16,11 +16,75 @@ use self::duplicate::DuplicateOptimizer;
 use self::redefinition::RedefinitionOptimizer;
 use self::tautology::TautologyOptimizer;
 
-use zokrates_ast::ir::{ProgIterator, Statement};
+use std::collections::HashSet;
+use zokrates_ast::ir::folder::Folder;
+use zokrates_ast::ir::visitor::Visitor;
+use zokrates_ast::ir::{Prog, ProgIterator, Statement, Variable};
 use zokrates_field::Field;
 
+/// Statistics on the size of a program before and after an optimizer pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OptimizationReport {
+    pub constraints_before: usize,
+    pub constraints_after: usize,
+    pub variables_before: usize,
+    pub variables_after: usize,
+    pub statements_before: usize,
+    pub statements_after: usize,
+}
+
+impl OptimizationReport {
+    pub fn statements_removed(&self) -> usize {
+        self.statements_before.saturating_sub(self.statements_after)
+    }
+}
+
+#[derive(Default)]
+struct VariableCollector {
+    variables: HashSet<Variable>,
+}
+
+impl<T: Field> Visitor<T> for VariableCollector {
+    fn visit_variable(&mut self, v: &Variable) {
+        self.variables.insert(*v);
+    }
+}
+
+fn variable_count<T: Field>(p: &Prog<T>) -> usize {
+    let mut collector = VariableCollector::default();
+    collector.visit_module(p);
+    collector.variables.len()
+}
+
+/// Run `pass` over `p`, returning the optimized program along with a report of its effect
+pub fn run_pass<'ast, T: Field, F: Folder<'ast, T>>(
+    pass: &mut F,
+    p: Prog<'ast, T>,
+) -> (Prog<'ast, T>, OptimizationReport) {
+    let constraints_before = p.constraint_count();
+    let variables_before = variable_count(&p);
+    let statements_before = p.statements.len();
+
+    let p = pass.fold_program(p);
+
+    let report = OptimizationReport {
+        constraints_before,
+        constraints_after: p.constraint_count(),
+        variables_before,
+        variables_after: variable_count(&p),
+        statements_before,
+        statements_after: p.statements.len(),
+    };
+
+    log::debug!("Optimizer pass: {:?}", report);
+
+    (p, report)
+}
+
+/// Optimize `p`, remembering at most `directive_cache_size` distinct directives for deduplication if provided
 pub fn optimize<'ast, T: Field, I: IntoIterator<Item = Statement<'ast, T>>>(
     p: ProgIterator<'ast, T, I>,
+    directive_cache_size: Option<usize>,
 ) -> ProgIterator<'ast, T, impl IntoIterator<Item = Statement<'ast, T>>> {
     // remove redefinitions
     log::debug!("Optimizer: Remove redefinitions and tautologies and directives and duplicates");
@@ -28,12 +92,12 @@ pub fn optimize<'ast, T: Field, I: IntoIterator<Item = Statement<'ast, T>>>(
     // define all optimizer steps
     let mut redefinition_optimizer = RedefinitionOptimizer::init(&p);
     let mut tautologies_optimizer = TautologyOptimizer::default();
-    let mut directive_optimizer = DirectiveOptimizer::default();
+    let mut directive_optimizer = directive_cache_size
+        .map(DirectiveOptimizer::bounded)
+        .unwrap_or_default();
     let mut canonicalizer = Canonicalizer::default();
     let mut duplicate_optimizer = DuplicateOptimizer::default();
 
-    use zokrates_ast::ir::folder::Folder;
-
     let r = ProgIterator {
         arguments: p
             .arguments
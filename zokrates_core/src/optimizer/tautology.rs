@@ -2,6 +2,11 @@
 // ```
 // a * 1 == a
 // ```
+// as well as constraints which hold whatever the values of their variables, such as
+// ```
+// 0 * a == 0
+// 2 * 3 == 6
+// ```
 //
 // This makes the assumption that ~one has value 1, as should be guaranteed by the verifier
 
@@ -13,9 +18,32 @@ use zokrates_field::Field;
 #[derive(Default)]
 pub struct TautologyOptimizer;
 
+/// Returns true if `quad == lin` holds for any assignment of the variables
+fn is_satisfied_by_construction<T: Field>(quad: &QuadComb<T>, lin: &LinComb<T>) -> bool {
+    let left = quad.left.clone().reduce();
+    let right = quad.right.clone().reduce();
+    let lin = lin.clone().reduce();
+
+    if lin.is_zero() && (left.is_zero() || right.is_zero()) {
+        return true;
+    }
+
+    match (
+        left.try_constant(),
+        right.try_constant(),
+        lin.try_constant(),
+    ) {
+        (Ok(left), Ok(right), Ok(lin)) => left * right == lin,
+        _ => false,
+    }
+}
+
 impl<'ast, T: Field> Folder<'ast, T> for TautologyOptimizer {
     fn fold_statement(&mut self, s: Statement<'ast, T>) -> Vec<Statement<'ast, T>> {
         match s {
+            Statement::Constraint(quad, lin, _) if is_satisfied_by_construction(&quad, &lin) => {
+                vec![]
+            }
             Statement::Constraint(quad, lin, message) => match quad.try_linear() {
                 Ok(l) => {
                     if l == lin {
@@ -32,4 +60,61 @@ impl<'ast, T: Field> Folder<'ast, T> for TautologyOptimizer {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn remove_satisfied_by_construction() {
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![],
+            statements: vec![
+                // 0 * _0 == 0
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(LinComb::zero(), Variable::new(0).into()),
+                    LinComb::zero(),
+                    None,
+                ),
+                // _0 * (0 * _1) == 0 * _2
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        Variable::new(0).into(),
+                        LinComb::summand(0, Variable::new(1)),
+                    ),
+                    LinComb::summand(0, Variable::new(2)),
+                    None,
+                ),
+                // 2 * 3 == 6
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::summand(2, Variable::one()),
+                        LinComb::summand(3, Variable::one()),
+                    ),
+                    LinComb::summand(6, Variable::one()),
+                    None,
+                ),
+                // _0 * _0 == _1
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        Variable::new(0).into(),
+                        Variable::new(0).into(),
+                    ),
+                    Variable::new(1).into(),
+                    None,
+                ),
+            ],
+            return_count: 0,
+        };
+
+        let expected = vec![Statement::Constraint(
+            QuadComb::from_linear_combinations(Variable::new(0).into(), Variable::new(0).into()),
+            Variable::new(1).into(),
+            None,
+        )];
+
+        assert_eq!(
+            TautologyOptimizer::default().fold_program(p).statements,
+            expected
+        );
+    }
+}
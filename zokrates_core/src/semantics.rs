@@ -2101,7 +2101,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         e,
                         RuntimeError::SourceAssertion(
                             SourceMetadata::new(module_id.display().to_string(), pos.0)
-                                .message(message),
+                                .assertion_message(message),
                         ),
                     )),
                     e => Err(ErrorInner {
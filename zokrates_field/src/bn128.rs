@@ -259,6 +259,15 @@ mod tests {
             assert_eq!(fp, FieldPrime::from_byte_vector(bv));
         }
 
+        #[test]
+        fn canonicalize() {
+            let fp = FieldPrime::from(-1);
+            let canonical = fp.clone().canonicalize();
+            assert_eq!(canonical, fp);
+            assert_eq!(canonical.clone().canonicalize(), canonical);
+            assert_eq!(canonical.to_biguint(), FieldPrime::max_value().to_biguint());
+        }
+
         #[test]
         fn dec_string_ser_deser() {
             let fp = FieldPrime::from("101");
@@ -266,6 +275,23 @@ mod tests {
             assert_eq!(fp, FieldPrime::try_from_dec_str(&bv).unwrap());
         }
 
+        #[test]
+        fn from_dec_and_hex_str() {
+            assert_eq!(
+                FieldPrime::from_dec_str("255").unwrap(),
+                FieldPrime::from(255)
+            );
+            assert_eq!(
+                FieldPrime::from_hex_str("0xff").unwrap(),
+                FieldPrime::from(255)
+            );
+
+            let modulus = (FieldPrime::max_value().to_biguint() + 1u32).to_string();
+            assert!(FieldPrime::from_dec_str(&modulus).is_err());
+            assert!(FieldPrime::from_dec_str("12a").is_err());
+            assert!(FieldPrime::from_hex_str("ff").is_err());
+        }
+
         #[test]
         fn compact_representation() {
             let one = FieldPrime::from(1);
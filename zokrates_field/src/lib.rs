@@ -117,6 +117,16 @@ pub trait Field:
     /// Tries to parse a string into this representation
     fn try_from_dec_str(s: &str) -> Result<Self, FieldParseError>;
     fn try_from_str(s: &str, radix: u32) -> Result<Self, FieldParseError>;
+    /// Parses a decimal string, rejecting values which are not smaller than the modulus
+    fn from_dec_str(s: &str) -> Result<Self, FieldParseError> {
+        Self::try_from_str(s, 10)
+    }
+    /// Parses a `0x`-prefixed hexadecimal string, rejecting values which are not smaller than the modulus
+    fn from_hex_str(s: &str) -> Result<Self, FieldParseError> {
+        s.strip_prefix("0x")
+            .ok_or(FieldParseError)
+            .and_then(|s| Self::try_from_str(s, 16))
+    }
     /// Returns a decimal string representing a the member of the equivalence class of this `Field` in Z/pZ
     /// which lies in [-(p-1)/2, (p-1)/2]
     fn to_compact_dec_string(&self) -> String;
@@ -128,6 +138,11 @@ pub trait Field:
     fn bits(&self) -> u32;
     /// Returns the value as a BigUint
     fn to_biguint(&self) -> BigUint;
+    /// Returns the least non-negative residue of this element, i.e. its representative in [0, p).
+    /// Field elements are always held in this form, so this is the identity
+    fn canonicalize(self) -> Self {
+        self
+    }
 }
 
 #[macro_use]
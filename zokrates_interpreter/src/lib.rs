@@ -3,26 +3,42 @@ use std::collections::HashMap;
 use std::fmt;
 use zokrates_abi::{Decode, Value};
 use zokrates_ast::ir::{
-    LinComb, ProgIterator, QuadComb, RuntimeError, Solver, Statement, Variable, Witness,
+    Directive, LinComb, ProgIterator, QuadComb, RuntimeError, Solver, Statement, Variable, Witness,
 };
 use zokrates_ast::zir;
 use zokrates_field::Field;
 
+#[cfg(feature = "multicore")]
+use rayon::ThreadPool;
+
 pub type ExecutionResult<T> = Result<Witness<T>, Error>;
 
+/// Without the `multicore` feature, directives are solved sequentially and no thread pool is ever built
+#[cfg(not(feature = "multicore"))]
+enum ThreadPool {}
+
 #[derive(Default)]
 pub struct Interpreter {
     /// Whether we should try to give out-of-range bit decompositions when the input is not a single summand.
     /// Used to do targeted testing of `<` flattening, making sure the bit decomposition we base the result on is unique.
     should_try_out_of_range: bool,
+    /// The number of threads used by `execute_parallel`, defaulting to rayon's global pool if not set
+    thread_pool_size: Option<usize>,
 }
 
 impl Interpreter {
     pub fn try_out_of_range() -> Interpreter {
         Interpreter {
             should_try_out_of_range: true,
+            ..Default::default()
         }
     }
+
+    /// Cap the number of threads used by `execute_parallel` to `size`, using a pool scoped to each execution
+    pub fn with_thread_pool_size(mut self, size: usize) -> Self {
+        self.thread_pool_size = Some(size);
+        self
+    }
 }
 
 impl Interpreter {
@@ -53,70 +69,242 @@ impl Interpreter {
             witness.insert(arg.id, value.clone());
         }
 
+        for statement in program.statements.into_iter() {
+            self.execute_statement(statement, &mut witness, log_stream)?;
+        }
+
+        Ok(witness)
+    }
+
+    /// Same as `execute`, but consecutive directives are partitioned into dependency levels
+    /// and the directives of each level are solved in parallel when the `multicore` feature is enabled.
+    /// The resulting witness and logs are identical to the ones of `execute_with_log_stream`.
+    pub fn execute_parallel<
+        'ast,
+        W: std::io::Write,
+        T: Field,
+        I: IntoIterator<Item = Statement<'ast, T>>,
+    >(
+        &self,
+        program: ProgIterator<'ast, T, I>,
+        inputs: &[T],
+        log_stream: &mut W,
+    ) -> ExecutionResult<T> {
+        self.check_inputs(&program, inputs)?;
+        let mut witness = Witness::default();
+        witness.insert(Variable::one(), T::one());
+
+        for (arg, value) in program.arguments.iter().zip(inputs.iter()) {
+            witness.insert(arg.id, value.clone());
+        }
+
+        let pool = self.thread_pool()?;
+
+        let mut directives = vec![];
+
         for statement in program.statements.into_iter() {
             match statement {
-                Statement::Block(..) => unreachable!(),
-                Statement::Constraint(quad, lin, error) => match lin.is_assignee(&witness) {
-                    true => {
-                        let val = evaluate_quad(&witness, &quad).unwrap();
-                        witness.insert(lin.0.get(0).unwrap().0, val);
-                    }
-                    false => {
-                        let lhs_value = evaluate_quad(&witness, &quad).unwrap();
-                        let rhs_value = evaluate_lin(&witness, &lin).unwrap();
-                        if lhs_value != rhs_value {
-                            return Err(Error::UnsatisfiedConstraint { error });
-                        }
+                Statement::Directive(d) => directives.push(d),
+                statement => {
+                    self.solve_directives(
+                        std::mem::take(&mut directives),
+                        &mut witness,
+                        pool.as_ref(),
+                    )?;
+                    self.execute_statement(statement, &mut witness, log_stream)?;
+                }
+            }
+        }
+
+        self.solve_directives(directives, &mut witness, pool.as_ref())?;
+
+        Ok(witness)
+    }
+
+    fn execute_statement<'ast, W: std::io::Write, T: Field>(
+        &self,
+        statement: Statement<'ast, T>,
+        witness: &mut Witness<T>,
+        log_stream: &mut W,
+    ) -> Result<(), Error> {
+        match statement {
+            Statement::Block(..) => unreachable!(),
+            Statement::Constraint(quad, lin, error) => match lin.is_assignee(witness) {
+                true => {
+                    let val = evaluate_quad(witness, &quad).unwrap();
+                    witness.insert(lin.0.get(0).unwrap().0, val);
+                }
+                false => {
+                    let lhs_value = evaluate_quad(witness, &quad).unwrap();
+                    let rhs_value = evaluate_lin(witness, &lin).unwrap();
+                    if lhs_value != rhs_value {
+                        return Err(Error::UnsatisfiedConstraint { error });
                     }
-                },
-                Statement::Directive(ref d) => {
-                    let mut inputs: Vec<_> = d
-                        .inputs
+                }
+            },
+            Statement::Directive(ref d) => {
+                let res = self.solve_directive(d, witness)?;
+
+                for (i, o) in d.outputs.iter().enumerate() {
+                    witness.insert(*o, res[i].clone());
+                }
+            }
+            Statement::Log(l, expressions) => {
+                let mut parts = l.parts.into_iter();
+
+                write!(log_stream, "{}", parts.next().unwrap()).map_err(|_| Error::LogStream)?;
+
+                for ((t, e), part) in expressions.into_iter().zip(parts) {
+                    let values: Vec<_> = e
                         .iter()
-                        .map(|i| evaluate_quad(&witness, i).unwrap())
+                        .map(|e| evaluate_lin(witness, e).unwrap())
                         .collect();
 
-                    let res = match (&d.solver, self.should_try_out_of_range) {
-                        (Solver::Bits(bitwidth), true) if *bitwidth >= T::get_required_bits() => {
-                            Ok(Self::try_solve_with_out_of_range_bits(
-                                *bitwidth,
-                                inputs.pop().unwrap(),
-                            ))
-                        }
-                        _ => Self::execute_solver(&d.solver, &inputs),
-                    }
-                    .map_err(Error::Solver)?;
+                    write!(log_stream, "{}", Value::decode(values, t).into_serde_json())
+                        .map_err(|_| Error::LogStream)?;
 
-                    for (i, o) in d.outputs.iter().enumerate() {
-                        witness.insert(*o, res[i].clone());
-                    }
+                    write!(log_stream, "{}", part).map_err(|_| Error::LogStream)?;
                 }
-                Statement::Log(l, expressions) => {
-                    let mut parts = l.parts.into_iter();
 
-                    write!(log_stream, "{}", parts.next().unwrap())
-                        .map_err(|_| Error::LogStream)?;
+                writeln!(log_stream).map_err(|_| Error::LogStream)?;
 
-                    for ((t, e), part) in expressions.into_iter().zip(parts) {
-                        let values: Vec<_> = e
-                            .iter()
-                            .map(|e| evaluate_lin(&witness, e).unwrap())
-                            .collect();
+                log_stream.flush().map_err(|_| Error::LogStream)?;
+            }
+        }
 
-                        write!(log_stream, "{}", Value::decode(values, t).into_serde_json())
-                            .map_err(|_| Error::LogStream)?;
+        Ok(())
+    }
 
-                        write!(log_stream, "{}", part).map_err(|_| Error::LogStream)?;
-                    }
+    fn solve_directive<'ast, T: Field>(
+        &self,
+        d: &Directive<'ast, T>,
+        witness: &Witness<T>,
+    ) -> Result<Vec<T>, Error> {
+        let mut inputs: Vec<_> = d
+            .inputs
+            .iter()
+            .map(|i| evaluate_quad(witness, i).unwrap())
+            .collect();
+
+        match (&d.solver, self.should_try_out_of_range) {
+            (Solver::Bits(bitwidth), true) if *bitwidth >= T::get_required_bits() => Ok(
+                Self::try_solve_with_out_of_range_bits(*bitwidth, inputs.pop().unwrap()),
+            ),
+            _ => Self::execute_solver(&d.solver, &inputs),
+        }
+        .map_err(|error| Error::Solver {
+            directive: d.to_string(),
+            inputs: inputs.iter().map(|i| i.to_dec_string()).collect(),
+            error,
+        })
+    }
 
-                    writeln!(log_stream).map_err(|_| Error::LogStream)?;
+    #[cfg(feature = "multicore")]
+    fn thread_pool(&self) -> Result<Option<ThreadPool>, Error> {
+        self.thread_pool_size
+            .map(|size| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(size)
+                    .build()
+                    .map_err(|e| Error::ThreadPool(e.to_string()))
+            })
+            .transpose()
+    }
 
-                    log_stream.flush().map_err(|_| Error::LogStream)?;
+    #[cfg(not(feature = "multicore"))]
+    fn thread_pool(&self) -> Result<Option<ThreadPool>, Error> {
+        Ok(None)
+    }
+
+    /// Solve a sequence of directives, one dependency level at a time, in `pool` if provided
+    fn solve_directives<'ast, T: Field>(
+        &self,
+        directives: Vec<Directive<'ast, T>>,
+        witness: &mut Witness<T>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<(), Error> {
+        // the level of a directive is one more than the highest level of the directives it depends on
+        let mut variable_levels: HashMap<Variable, usize> = HashMap::new();
+        let mut levels: Vec<Vec<(usize, Directive<'ast, T>)>> = vec![];
+
+        for (index, d) in directives.into_iter().enumerate() {
+            let level = d
+                .inputs
+                .iter()
+                .flat_map(|q| q.left.0.iter().chain(q.right.0.iter()))
+                .filter_map(|(v, _)| variable_levels.get(v))
+                .map(|level| level + 1)
+                .max()
+                .unwrap_or(0);
+
+            for o in &d.outputs {
+                variable_levels.insert(*o, level);
+            }
+
+            if levels.len() <= level {
+                levels.push(vec![]);
+            }
+            levels[level].push((index, d));
+        }
+
+        // report the first failing directive in program order, like sequential solving does. The directives before it
+        // only depend on directives before them, so they can all still be solved, and the ones after it are skipped
+        let mut first_error: Option<(usize, Error)> = None;
+
+        for level in levels {
+            let level: Vec<_> = match &first_error {
+                Some((failed, _)) => level.into_iter().filter(|(i, _)| i < failed).collect(),
+                None => level,
+            };
+
+            #[cfg(feature = "multicore")]
+            let results: Vec<_> = {
+                use rayon::prelude::*;
+                let witness = &*witness;
+                let solve = || {
+                    level
+                        .par_iter()
+                        .map(|(_, d)| self.solve_directive(d, witness))
+                        .collect::<Vec<_>>()
+                };
+                match pool {
+                    Some(pool) => pool.install(solve),
+                    None => solve(),
+                }
+            };
+            #[cfg(not(feature = "multicore"))]
+            let results: Vec<_> = {
+                // no pool can exist without the `multicore` feature
+                let _ = pool;
+                level
+                    .iter()
+                    .map(|(_, d)| self.solve_directive(d, witness))
+                    .collect()
+            };
+
+            for ((index, d), res) in level.iter().zip(results) {
+                match res {
+                    Ok(res) => {
+                        for (i, o) in d.outputs.iter().enumerate() {
+                            witness.insert(*o, res[i].clone());
+                        }
+                    }
+                    Err(e) => {
+                        if first_error
+                            .as_ref()
+                            .map_or(true, |(failed, _)| index < failed)
+                        {
+                            first_error = Some((*index, e));
+                        }
+                    }
                 }
             }
         }
 
-        Ok(witness)
+        match first_error {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
     }
 
     fn try_solve_with_out_of_range_bits<T: Field>(bit_width: usize, input: T) -> Vec<T> {
@@ -347,10 +535,20 @@ pub struct EvaluationError;
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Error {
-    UnsatisfiedConstraint { error: Option<RuntimeError> },
-    Solver(String),
-    WrongInputCount { expected: usize, received: usize },
+    UnsatisfiedConstraint {
+        error: Option<RuntimeError>,
+    },
+    Solver {
+        directive: String,
+        inputs: Vec<String>,
+        error: String,
+    },
+    WrongInputCount {
+        expected: usize,
+        received: usize,
+    },
     LogStream,
+    ThreadPool(String),
 }
 
 fn evaluate_lin<T: Field>(w: &Witness<T>, l: &LinComb<T>) -> Result<T, EvaluationError> {
@@ -391,7 +589,17 @@ impl fmt::Display for Error {
                     _ => write!(f, ""),
                 }
             }
-            Error::Solver(ref e) => write!(f, "Solver error: {}", e),
+            Error::Solver {
+                ref directive,
+                ref inputs,
+                ref error,
+            } => write!(
+                f,
+                "Solver error in `{}` with inputs [{}]: {}",
+                directive,
+                inputs.join(", "),
+                error
+            ),
             Error::WrongInputCount { expected, received } => write!(
                 f,
                 "Program takes {} input{} but was passed {} value{}",
@@ -401,6 +609,7 @@ impl fmt::Display for Error {
                 if received == 1 { "" } else { "s" }
             ),
             Error::LogStream => write!(f, "Error writing a log to the log stream"),
+            Error::ThreadPool(ref e) => write!(f, "Could not build a thread pool: {}", e),
         }
     }
 }
@@ -494,4 +703,240 @@ mod tests {
 
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn parallel_directives() {
+        use zokrates_ast::ir::{Parameter, Prog};
+        use zokrates_ast::typed::ConcreteType;
+
+        // _2 = _0 / _1 and (_3, _4) = ConditionEq(_0) are independent, the bits of _2 depend on the first one
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::private(Variable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(0).into(), Variable::new(1).into()],
+                    outputs: vec![Variable::new(2)],
+                    solver: Solver::Div,
+                }),
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(0).into()],
+                    outputs: vec![Variable::new(3), Variable::new(4)],
+                    solver: Solver::ConditionEq,
+                }),
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(2).into()],
+                    outputs: (5..9).map(Variable::new).collect(),
+                    solver: Solver::Bits(4),
+                }),
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        Variable::new(2).into(),
+                        Variable::new(1).into(),
+                    ),
+                    Variable::new(0).into(),
+                    None,
+                ),
+                Statement::Log(
+                    "_2 = {}".into(),
+                    vec![(ConcreteType::FieldElement, vec![Variable::new(2).into()])],
+                ),
+                Statement::Constraint(
+                    LinComb::from(Variable::new(2)).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let inputs = vec![Bn128Field::from(12), Bn128Field::from(3)];
+
+        let interpreter = Interpreter::default();
+
+        let mut sequential_logs = vec![];
+        let sequential = interpreter
+            .execute_with_log_stream(prog.clone(), &inputs, &mut sequential_logs)
+            .unwrap();
+        let mut parallel_logs = vec![];
+        let parallel = interpreter
+            .execute_parallel(prog, &inputs, &mut parallel_logs)
+            .unwrap();
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(parallel.0[&Variable::public(0)], Bn128Field::from(4));
+        assert_eq!(sequential_logs, parallel_logs);
+        assert_eq!(String::from_utf8(parallel_logs).unwrap(), "_2 = \"4\"\n");
+    }
+
+    #[test]
+    fn capped_thread_pool() {
+        use zokrates_ast::ir::{Parameter, Prog};
+
+        // (_1, _2) = ConditionEq(_0) and _3..=_10 = Bits(_0) are independent
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![Parameter::private(Variable::new(0))],
+            return_count: 1,
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(0).into()],
+                    outputs: vec![Variable::new(1), Variable::new(2)],
+                    solver: Solver::ConditionEq,
+                }),
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(0).into()],
+                    outputs: (3..11).map(Variable::new).collect(),
+                    solver: Solver::Bits(8),
+                }),
+                Statement::Constraint(
+                    LinComb::from(Variable::new(10)).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let inputs = vec![Bn128Field::from(13)];
+
+        let default = Interpreter::default()
+            .execute_parallel(prog.clone(), &inputs, &mut std::io::sink())
+            .unwrap();
+        let capped = Interpreter::default()
+            .with_thread_pool_size(1)
+            .execute_parallel(prog, &inputs, &mut std::io::sink())
+            .unwrap();
+
+        assert_eq!(default, capped);
+        assert_eq!(capped.0[&Variable::public(0)], Bn128Field::from(1));
+    }
+
+    #[test]
+    fn solver_error() {
+        use zokrates_ast::ir::{Parameter, Prog};
+        use zokrates_ast::zir::Id;
+
+        // def id(bool a) -> bool { return a; }
+        let id = zir::ZirFunction {
+            arguments: vec![zir::Parameter {
+                id: zir::Variable::boolean("a".into()),
+                private: true,
+            }],
+            statements: vec![zir::ZirStatement::Return(vec![
+                zir::BooleanExpression::identifier("a".into()).into(),
+            ])],
+            signature: zir::types::Signature::new()
+                .inputs(vec![zir::Type::Boolean])
+                .outputs(vec![zir::Type::Boolean]),
+        };
+
+        let directive = Directive {
+            inputs: vec![Variable::new(0).into()],
+            outputs: vec![Variable::new(1)],
+            solver: Solver::Zir(id),
+        };
+
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![Parameter::private(Variable::new(0))],
+            return_count: 1,
+            statements: vec![
+                Statement::Directive(directive.clone()),
+                Statement::Constraint(
+                    LinComb::from(Variable::new(1)).into(),
+                    Variable::public(0).into(),
+                    None,
+                ),
+            ],
+        };
+
+        let res = Interpreter::default().execute(prog, &[Bn128Field::from(2)]);
+
+        assert_eq!(
+            res,
+            Err(Error::Solver {
+                directive: directive.to_string(),
+                inputs: vec!["2".into()],
+                error: "`a` has unexpected value `2`".into()
+            })
+        );
+    }
+
+    #[test]
+    fn parallel_solver_errors() {
+        use zokrates_ast::ir::{Parameter, Prog};
+        use zokrates_ast::zir::Id;
+
+        // def id(bool a) -> bool { return a; }
+        let id = zir::ZirFunction {
+            arguments: vec![zir::Parameter {
+                id: zir::Variable::boolean("a".into()),
+                private: true,
+            }],
+            statements: vec![zir::ZirStatement::Return(vec![
+                zir::BooleanExpression::identifier("a".into()).into(),
+            ])],
+            signature: zir::types::Signature::new()
+                .inputs(vec![zir::Type::Boolean])
+                .outputs(vec![zir::Type::Boolean]),
+        };
+
+        let id_directive = |input, output| {
+            Statement::Directive(Directive {
+                inputs: vec![Variable::new(input).into()],
+                outputs: vec![Variable::new(output)],
+                solver: Solver::Zir(id.clone()),
+            })
+        };
+
+        let interpreter = Interpreter::default();
+
+        // id(_0) and id(_1) are in the same level and both fail
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::private(Variable::new(1)),
+            ],
+            return_count: 0,
+            statements: vec![id_directive(0, 2), id_directive(1, 3)],
+        };
+
+        let inputs = vec![Bn128Field::from(2), Bn128Field::from(3)];
+
+        let sequential = interpreter.execute(prog.clone(), &inputs).unwrap_err();
+        let parallel = interpreter
+            .execute_parallel(prog, &inputs, &mut std::io::sink())
+            .unwrap_err();
+
+        assert_eq!(parallel, sequential);
+        assert!(matches!(parallel, Error::Solver { inputs, .. } if inputs == vec!["2"]));
+
+        // id(_2) comes first but is in a later level than id(_1), as it depends on _2 = _0 / _1
+        let prog: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                Parameter::private(Variable::new(0)),
+                Parameter::private(Variable::new(1)),
+            ],
+            return_count: 0,
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![Variable::new(0).into(), Variable::new(1).into()],
+                    outputs: vec![Variable::new(2)],
+                    solver: Solver::Div,
+                }),
+                id_directive(2, 3),
+                id_directive(1, 4),
+            ],
+        };
+
+        let inputs = vec![Bn128Field::from(6), Bn128Field::from(3)];
+
+        let sequential = interpreter.execute(prog.clone(), &inputs).unwrap_err();
+        let parallel = interpreter
+            .execute_parallel(prog, &inputs, &mut std::io::sink())
+            .unwrap_err();
+
+        assert_eq!(parallel, sequential);
+        assert!(matches!(parallel, Error::Solver { inputs, .. } if inputs == vec!["2"]));
+    }
 }
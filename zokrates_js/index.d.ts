@@ -14,6 +14,7 @@ declare module "zokrates-js" {
   export interface CompileConfig {
     isolate_branches?: boolean;
     debug?: boolean;
+    directive_cache_size?: number;
   }
 
   export interface CompileOptions {
@@ -0,0 +1,609 @@
+//! Compressed binary encoding of bn128 curve points
+//!
+//! A point is written as its `x` coordinate only, as 32 big-endian bytes per base field element, with the parity
+//! of `y` stored in the most significant bit of the first byte. G2 coordinates are written as `c0` then `c1`, and
+//! the parity of a G2 `y` is the one of its first non-zero component. The point at infinity, which ZoKrates
+//! represents as `(0, 0)`, is written as zero with the second most significant bit set.
+//! Reading a point recovers `y` from the curve equation, and fails if `x` is not the abscissa of a curve point,
+//! if a G2 point is outside of the subgroup of order `r`, or if the point at infinity is not encoded as zero.
+
+use crate::{Fq, G1Affine, G2Affine, G2AffineFq2};
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use std::io::{self, Read, Write};
+
+const FQ_BYTES: usize = 32;
+const G1_BYTES: usize = FQ_BYTES;
+const G2_BYTES: usize = 2 * FQ_BYTES;
+const PARITY_FLAG: u8 = 0x80;
+const INFINITY_FLAG: u8 = 0x40;
+
+fn parse_decimal(e: &str) -> BigUint {
+    BigUint::parse_bytes(e.as_bytes(), 10).unwrap()
+}
+
+lazy_static! {
+    /// The modulus of the bn128 base field
+    static ref MODULUS: BigUint = parse_decimal(
+        "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+    );
+    /// The order of the bn128 G1 and G2 subgroups
+    static ref ORDER: BigUint = parse_decimal(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+    );
+    /// The constant term of the equation of the twist on which bn128 G2 is defined, `3 / (9 + u)`
+    static ref TWIST_B: Fq2 = Fq2(
+        parse_decimal(
+            "19485874751759354771024239261021720505790618469301721065564631296452457478373"
+        ),
+        parse_decimal(
+            "266929791119991161246907387137283842545076965332900288569378510910307636690"
+        ),
+    );
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn modulus() -> &'static BigUint {
+    &MODULUS
+}
+
+fn is_odd(e: &BigUint) -> bool {
+    e.to_bytes_le()[0] & 1 == 1
+}
+
+fn is_zero(e: &BigUint) -> bool {
+    e == &BigUint::from(0u32)
+}
+
+fn neg(e: &BigUint) -> BigUint {
+    (modulus() - e) % modulus()
+}
+
+fn parse_fq(e: &Fq) -> io::Result<BigUint> {
+    let bytes = hex::decode(e.trim_start_matches("0x")).map_err(|e| invalid_data(e.to_string()))?;
+    let e = BigUint::from_bytes_be(&bytes);
+
+    match &e < modulus() {
+        true => Ok(e),
+        false => Err(invalid_data(format!(
+            "coordinate {} is not smaller than the field modulus",
+            e
+        ))),
+    }
+}
+
+fn fq_to_bytes(e: &BigUint) -> [u8; FQ_BYTES] {
+    let bytes = e.to_bytes_be();
+    let mut res = [0; FQ_BYTES];
+    res[FQ_BYTES - bytes.len()..].copy_from_slice(&bytes);
+    res
+}
+
+fn format_fq(e: &BigUint) -> Fq {
+    format!("0x{}", hex::encode(fq_to_bytes(e)))
+}
+
+/// Read exactly `len` bytes in a single call, failing if the reader holds less
+fn read_bytes<R: Read>(reader: &mut R, len: usize, name: &str) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "truncated compressed {} element: expected {} bytes",
+                name, len
+            ),
+        )
+    })?;
+    Ok(bytes)
+}
+
+/// The size in bytes of `count` elements of `size` bytes, failing on overflow
+fn vec_bytes(count: usize, size: usize, name: &str) -> io::Result<usize> {
+    count.checked_mul(size).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("too many compressed {} elements: {}", name, count),
+        )
+    })
+}
+
+/// Decode base field elements, returning them along with the flags found in the first byte
+fn decode_flagged(bytes: &[u8], name: &str) -> io::Result<(u8, Vec<BigUint>)> {
+    let flags = bytes[0] & (PARITY_FLAG | INFINITY_FLAG);
+
+    // the flags are the top bits of the first element, remove them from it
+    let elements = bytes
+        .chunks(FQ_BYTES)
+        .enumerate()
+        .map(|(i, chunk)| match i {
+            0 => BigUint::from_bytes_be(chunk) - (BigUint::from(flags) << (8 * (FQ_BYTES - 1))),
+            _ => BigUint::from_bytes_be(chunk),
+        })
+        .collect::<Vec<_>>();
+
+    match elements.iter().all(|e| e < modulus()) {
+        true => Ok((flags, elements)),
+        false => Err(invalid_data(format!(
+            "invalid compressed {} element: coordinate is not smaller than the field modulus",
+            name
+        ))),
+    }
+}
+
+fn write_flagged<W: Write>(writer: &mut W, flags: u8, elements: &[&BigUint]) -> io::Result<()> {
+    let mut bytes: Vec<u8> = elements.iter().flat_map(|e| fq_to_bytes(e)).collect();
+    bytes[0] |= flags;
+    writer.write_all(&bytes)
+}
+
+/// Elements of the quadratic extension `Fq[u] / (u^2 + 1)`
+#[derive(Clone, PartialEq)]
+struct Fq2(BigUint, BigUint);
+
+impl Fq2 {
+    fn zero() -> Self {
+        Fq2(BigUint::from(0u32), BigUint::from(0u32))
+    }
+
+    fn one() -> Self {
+        Fq2(BigUint::from(1u32), BigUint::from(0u32))
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let p = modulus();
+        Fq2((&self.0 + &other.0) % p, (&self.1 + &other.1) % p)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let p = modulus();
+        Fq2(
+            (&self.0 * &other.0 + neg(&(&self.1 * &other.1 % p))) % p,
+            (&self.0 * &other.1 + &self.1 * &other.0) % p,
+        )
+    }
+
+    fn neg(&self) -> Self {
+        Fq2(neg(&self.0), neg(&self.1))
+    }
+
+    fn pow(&self, exponent: &BigUint) -> Self {
+        exponent
+            .to_bytes_be()
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .fold(Fq2::one(), |acc, bit| {
+                let acc = acc.mul(&acc);
+                match bit {
+                    true => acc.mul(self),
+                    false => acc,
+                }
+            })
+    }
+
+    fn is_odd(&self) -> bool {
+        match is_zero(&self.0) {
+            true => is_odd(&self.1),
+            false => is_odd(&self.0),
+        }
+    }
+
+    /// Square root for `p = 3 mod 4`, following algorithm 9 of <https://eprint.iacr.org/2012/685.pdf>
+    fn sqrt(&self) -> Option<Self> {
+        let p = modulus();
+        let minus_one = Fq2::one().neg();
+
+        let a1 = self.pow(&((p - 3u32) >> 2));
+        let alpha = a1.mul(&a1).mul(self);
+        let a0 = alpha.pow(p).mul(&alpha);
+
+        if a0 == minus_one {
+            return None;
+        }
+
+        let x0 = a1.mul(self);
+
+        let root = match alpha == minus_one {
+            true => Fq2(BigUint::from(0u32), BigUint::from(1u32)).mul(&x0),
+            false => Fq2::one().add(&alpha).pow(&((p - 1u32) >> 1)).mul(&x0),
+        };
+
+        match root.mul(&root) == *self {
+            true => Some(root),
+            false => None,
+        }
+    }
+}
+
+/// A point of the twist in jacobian coordinates `(X, Y, Z)`, standing for `(X / Z^2, Y / Z^3)`, with `Z = 0` at
+/// infinity
+struct G2Jacobian(Fq2, Fq2, Fq2);
+
+impl G2Jacobian {
+    fn infinity() -> Self {
+        G2Jacobian(Fq2::zero(), Fq2::one(), Fq2::zero())
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.2 == Fq2::zero()
+    }
+
+    /// Doubling on a curve with `a = 0`, see <https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html>
+    fn double(&self) -> Self {
+        if self.is_infinity() {
+            return G2Jacobian(self.0.clone(), self.1.clone(), self.2.clone());
+        }
+
+        let a = self.0.mul(&self.0);
+        let b = self.1.mul(&self.1);
+        let c = b.mul(&b);
+        let x_plus_b = self.0.add(&b);
+        let d = x_plus_b.mul(&x_plus_b).sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let x = e.mul(&e).sub(&d.double());
+        let y = e.mul(&d.sub(&x)).sub(&c.double().double().double());
+        let z = self.1.mul(&self.2).double();
+
+        G2Jacobian(x, y, z)
+    }
+
+    /// Addition of a point in affine coordinates
+    fn add_affine(&self, x2: &Fq2, y2: &Fq2) -> Self {
+        if self.is_infinity() {
+            return G2Jacobian(x2.clone(), y2.clone(), Fq2::one());
+        }
+
+        let z1z1 = self.2.mul(&self.2);
+        let h = x2.mul(&z1z1).sub(&self.0);
+        let r = y2.mul(&self.2).mul(&z1z1).sub(&self.1);
+
+        if h == Fq2::zero() {
+            return match r == Fq2::zero() {
+                true => self.double(),
+                false => G2Jacobian::infinity(),
+            };
+        }
+
+        let hh = h.mul(&h);
+        let hhh = hh.mul(&h);
+        let v = self.0.mul(&hh);
+        let x = r.mul(&r).sub(&hhh).sub(&v.double());
+        let y = r.mul(&v.sub(&x)).sub(&self.1.mul(&hhh));
+        let z = self.2.mul(&h);
+
+        G2Jacobian(x, y, z)
+    }
+}
+
+/// Check that the curve point `(x, y)` of the twist is in the subgroup of order `r`, which is not the whole curve
+/// as the cofactor of bn128 G2 is not one
+fn is_in_g2_subgroup(x: &Fq2, y: &Fq2) -> bool {
+    ORDER
+        .to_bytes_be()
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+        .fold(G2Jacobian::infinity(), |acc, bit| {
+            let acc = acc.double();
+            match bit {
+                true => acc.add_affine(x, y),
+                false => acc,
+            }
+        })
+        .is_infinity()
+}
+
+pub fn write_g1_compressed<W: Write>(writer: &mut W, g1: &G1Affine) -> io::Result<()> {
+    let (x, y) = (parse_fq(&g1.0)?, parse_fq(&g1.1)?);
+
+    let flags = match (is_zero(&x) && is_zero(&y), is_odd(&y)) {
+        (true, _) => INFINITY_FLAG,
+        (false, true) => PARITY_FLAG,
+        (false, false) => 0,
+    };
+
+    write_flagged(writer, flags, &[&x])
+}
+
+fn decompress_g1(bytes: &[u8]) -> io::Result<G1Affine> {
+    let (flags, mut x) = decode_flagged(bytes, "G1")?;
+    let x = x.pop().unwrap();
+
+    if flags & INFINITY_FLAG != 0 {
+        return match flags & PARITY_FLAG == 0 && is_zero(&x) {
+            true => Ok(G1Affine(format_fq(&x), format_fq(&x))),
+            false => Err(invalid_data(
+                "invalid compressed G1 element: the point at infinity must be encoded as zero"
+                    .to_string(),
+            )),
+        };
+    }
+
+    // y^2 = x^3 + 3, and p = 3 mod 4 so that a square root of `a` is `a^((p + 1) / 4)`
+    let p = modulus();
+    let y2 = (x.modpow(&BigUint::from(3u32), p) + 3u32) % p;
+    let y = y2.modpow(&((p + 1u32) >> 2), p);
+
+    if y.modpow(&BigUint::from(2u32), p) != y2 {
+        return Err(invalid_data(format!(
+            "invalid compressed G1 element: {} is not the abscissa of a curve point",
+            x
+        )));
+    }
+
+    let y = match is_odd(&y) == (flags & PARITY_FLAG != 0) {
+        true => y,
+        false => neg(&y),
+    };
+
+    Ok(G1Affine(format_fq(&x), format_fq(&y)))
+}
+
+pub fn read_g1_compressed<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+    decompress_g1(&read_bytes(reader, G1_BYTES, "G1")?)
+}
+
+/// Read `count` consecutive compressed G1 elements with a single read
+pub fn read_g1_compressed_vec<R: Read>(reader: &mut R, count: usize) -> io::Result<Vec<G1Affine>> {
+    read_bytes(reader, vec_bytes(count, G1_BYTES, "G1")?, "G1")?
+        .chunks(G1_BYTES)
+        .map(decompress_g1)
+        .collect()
+}
+
+pub fn write_g2_compressed<W: Write>(writer: &mut W, g2: &G2Affine) -> io::Result<()> {
+    match g2 {
+        G2Affine::Fq2(G2AffineFq2(x, y)) => {
+            let x = Fq2(parse_fq(&x.0)?, parse_fq(&x.1)?);
+            let y = Fq2(parse_fq(&y.0)?, parse_fq(&y.1)?);
+
+            let flags = match (x == Fq2::zero() && y == Fq2::zero(), y.is_odd()) {
+                (true, _) => INFINITY_FLAG,
+                (false, true) => PARITY_FLAG,
+                (false, false) => 0,
+            };
+
+            write_flagged(writer, flags, &[&x.0, &x.1])
+        }
+        G2Affine::Fq(_) => Err(invalid_data(
+            "compressed G2 elements are only supported over a quadratic extension".to_string(),
+        )),
+    }
+}
+
+fn decompress_g2(bytes: &[u8]) -> io::Result<G2Affine> {
+    let (flags, x) = decode_flagged(bytes, "G2")?;
+    let x = Fq2(x[0].clone(), x[1].clone());
+
+    if flags & INFINITY_FLAG != 0 && (flags & PARITY_FLAG != 0 || x != Fq2::zero()) {
+        return Err(invalid_data(
+            "invalid compressed G2 element: the point at infinity must be encoded as zero"
+                .to_string(),
+        ));
+    }
+
+    let y = match flags & INFINITY_FLAG != 0 {
+        true => Fq2::zero(),
+        false => {
+            let y = x.mul(&x).mul(&x).add(&TWIST_B).sqrt().ok_or_else(|| {
+                invalid_data(
+                    "invalid compressed G2 element: not the abscissa of a curve point".to_string(),
+                )
+            })?;
+
+            if !is_in_g2_subgroup(&x, &y) {
+                return Err(invalid_data(
+                    "invalid compressed G2 element: not in the subgroup of order r".to_string(),
+                ));
+            }
+
+            match y.is_odd() == (flags & PARITY_FLAG != 0) {
+                true => y,
+                false => y.neg(),
+            }
+        }
+    };
+
+    Ok(G2Affine::Fq2(G2AffineFq2(
+        (format_fq(&x.0), format_fq(&x.1)),
+        (format_fq(&y.0), format_fq(&y.1)),
+    )))
+}
+
+pub fn read_g2_compressed<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+    decompress_g2(&read_bytes(reader, G2_BYTES, "G2")?)
+}
+
+/// Read `count` consecutive compressed G2 elements with a single read
+pub fn read_g2_compressed_vec<R: Read>(reader: &mut R, count: usize) -> io::Result<Vec<G2Affine>> {
+    read_bytes(reader, vec_bytes(count, G2_BYTES, "G2")?, "G2")?
+        .chunks(G2_BYTES)
+        .map(decompress_g2)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fq(e: &str) -> Fq {
+        format_fq(&BigUint::parse_bytes(e.as_bytes(), 10).unwrap())
+    }
+
+    fn neg_fq(e: &str) -> Fq {
+        format_fq(&neg(&BigUint::parse_bytes(e.as_bytes(), 10).unwrap()))
+    }
+
+    /// The G1 generator `(1, 2)` and its opposite, which have different parities
+    fn g1_points() -> [G1Affine; 2] {
+        [G1Affine(fq("1"), fq("2")), G1Affine(fq("1"), neg_fq("2"))]
+    }
+
+    /// The G2 generator and its opposite
+    fn g2_points() -> [G2Affine; 2] {
+        let x = (
+            "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+            "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+        );
+        let y = (
+            "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+            "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+        );
+
+        [
+            G2Affine::Fq2(G2AffineFq2((fq(x.0), fq(x.1)), (fq(y.0), fq(y.1)))),
+            G2Affine::Fq2(G2AffineFq2((fq(x.0), fq(x.1)), (neg_fq(y.0), neg_fq(y.1)))),
+        ]
+    }
+
+    #[test]
+    fn g1_round_trip() {
+        for g1 in g1_points() {
+            let mut buffer = vec![];
+            write_g1_compressed(&mut buffer, &g1).unwrap();
+            assert_eq!(buffer.len(), 32);
+
+            let read = read_g1_compressed(&mut &buffer[..]).unwrap();
+            assert_eq!((read.0, read.1), (g1.0, g1.1));
+        }
+    }
+
+    #[test]
+    fn g2_round_trip() {
+        for g2 in g2_points() {
+            let mut buffer = vec![];
+            write_g2_compressed(&mut buffer, &g2).unwrap();
+            assert_eq!(buffer.len(), 64);
+
+            let read = read_g2_compressed(&mut &buffer[..]).unwrap();
+            assert_eq!(read.to_string(), g2.to_string());
+        }
+    }
+
+    #[test]
+    fn g1_vec() {
+        let points: Vec<_> = g1_points().iter().cycle().take(4096).cloned().collect();
+
+        let mut buffer = vec![];
+        for g1 in &points {
+            write_g1_compressed(&mut buffer, g1).unwrap();
+        }
+
+        let mut reader = &buffer[..];
+        let read = read_g1_compressed_vec(&mut reader, points.len()).unwrap();
+
+        assert!(reader.is_empty());
+        assert_eq!(
+            read.iter().map(|g1| g1.to_string()).collect::<Vec<_>>(),
+            points.iter().map(|g1| g1.to_string()).collect::<Vec<_>>()
+        );
+
+        let error = read_g1_compressed_vec(&mut &buffer[..], points.len() + 1).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        let error = read_g1_compressed_vec(&mut &buffer[..], usize::MAX).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn g2_vec() {
+        let points: Vec<_> = g2_points().iter().cycle().take(256).cloned().collect();
+
+        let mut buffer = vec![];
+        for g2 in &points {
+            write_g2_compressed(&mut buffer, g2).unwrap();
+        }
+
+        let mut reader = &buffer[..];
+        let read = read_g2_compressed_vec(&mut reader, points.len()).unwrap();
+
+        assert!(reader.is_empty());
+        assert_eq!(
+            read.iter().map(|g2| g2.to_string()).collect::<Vec<_>>(),
+            points.iter().map(|g2| g2.to_string()).collect::<Vec<_>>()
+        );
+
+        let error = read_g2_compressed_vec(&mut &buffer[..], points.len() + 1).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        let error = read_g2_compressed_vec(&mut &buffer[..], usize::MAX).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn infinity() {
+        let g1 = G1Affine(fq("0"), fq("0"));
+
+        let mut buffer = vec![];
+        write_g1_compressed(&mut buffer, &g1).unwrap();
+        assert_eq!(buffer[0], INFINITY_FLAG);
+
+        let read = read_g1_compressed(&mut &buffer[..]).unwrap();
+        assert_eq!((read.0, read.1), (g1.0, g1.1));
+    }
+
+    #[test]
+    fn not_on_curve() {
+        // x^3 + 3 is not a square for x = 4
+        let mut buffer = vec![0; 32];
+        buffer[31] = 4;
+
+        let error = read_g1_compressed(&mut &buffer[..]).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "invalid compressed G1 element: 4 is not the abscissa of a curve point"
+        );
+    }
+
+    #[test]
+    fn non_zero_infinity() {
+        // the infinity flag along with a non-zero abscissa
+        let mut buffer = vec![0; 32];
+        buffer[0] = INFINITY_FLAG;
+        buffer[31] = 1;
+
+        let error = read_g1_compressed(&mut &buffer[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // the infinity flag along with the parity flag
+        let mut buffer = vec![0; 64];
+        buffer[0] = INFINITY_FLAG | PARITY_FLAG;
+
+        let error = read_g2_compressed(&mut &buffer[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        buffer[0] = INFINITY_FLAG;
+        let read = read_g2_compressed(&mut &buffer[..]).unwrap();
+        assert_eq!(
+            read.to_string(),
+            G2Affine::Fq2(G2AffineFq2((fq("0"), fq("0")), (fq("0"), fq("0")))).to_string()
+        );
+    }
+
+    #[test]
+    fn not_in_g2_subgroup() {
+        // x = 1 is the abscissa of a point of the twist which is not in the subgroup of order r
+        let mut buffer = vec![0; 64];
+        buffer[31] = 1;
+
+        let error = read_g2_compressed(&mut &buffer[..]).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "invalid compressed G2 element: not in the subgroup of order r"
+        );
+    }
+}
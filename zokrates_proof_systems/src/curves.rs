@@ -0,0 +1,106 @@
+/// Metadata about a curve supported by the proof systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveInfo {
+    /// The name of the curve, as returned by `Field::name` for its scalar field
+    pub name: &'static str,
+    /// The modulus of the scalar field, in decimal
+    pub scalar_modulus: &'static str,
+    /// The modulus of the base field, in decimal
+    pub base_modulus: &'static str,
+    /// Whether the backends can encode points of this curve in compressed form
+    pub compressed_points: bool,
+    /// Whether the EVM provides precompiles for pairings on this curve
+    pub evm_precompile: bool,
+}
+
+const SUPPORTED_CURVES: [CurveInfo; 4] = [
+    CurveInfo {
+        name: "bn128",
+        scalar_modulus:
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        base_modulus:
+            "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+        compressed_points: true,
+        evm_precompile: true,
+    },
+    CurveInfo {
+        name: "bls12_381",
+        scalar_modulus:
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+        base_modulus: "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787",
+        compressed_points: false,
+        evm_precompile: false,
+    },
+    CurveInfo {
+        name: "bls12_377",
+        scalar_modulus:
+            "8444461749428370424248824938781546531375899335154063827935233455917409239041",
+        base_modulus: "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177",
+        compressed_points: false,
+        evm_precompile: false,
+    },
+    CurveInfo {
+        name: "bw6_761",
+        scalar_modulus: "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177",
+        base_modulus: "6891450384315732539396789682275657542479668912536150109513790160209623422243491736087683183289411687640864567753786613451161759120554247759349511699125301598951605099378508850372543631423596795951899700429969112842764913119068299",
+        compressed_points: false,
+        evm_precompile: false,
+    },
+];
+
+/// Returns the curves supported by the proof systems, along with their parameters
+pub fn supported_curves() -> &'static [CurveInfo] {
+    &SUPPORTED_CURVES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
+
+    fn scalar_modulus<T: Field>() -> String {
+        (T::max_value().to_biguint() + 1u32).to_string()
+    }
+
+    #[test]
+    fn registry() {
+        let expected = vec![
+            (Bn128Field::name(), scalar_modulus::<Bn128Field>()),
+            (Bls12_381Field::name(), scalar_modulus::<Bls12_381Field>()),
+            (Bls12_377Field::name(), scalar_modulus::<Bls12_377Field>()),
+            (Bw6_761Field::name(), scalar_modulus::<Bw6_761Field>()),
+        ];
+
+        let curves: Vec<_> = supported_curves()
+            .iter()
+            .map(|c| (c.name, c.scalar_modulus.to_string()))
+            .collect();
+
+        assert_eq!(curves, expected);
+
+        // the scalar field of bw6_761 is the base field of bls12_377
+        assert_eq!(
+            supported_curves()[3].scalar_modulus,
+            supported_curves()[2].base_modulus
+        );
+
+        assert_eq!(
+            supported_curves()
+                .iter()
+                .filter(|c| c.evm_precompile)
+                .map(|c| c.name)
+                .collect::<Vec<_>>(),
+            vec!["bn128"]
+        );
+
+        // only bn128 points have a compressed encoding
+        assert_eq!(
+            supported_curves()
+                .iter()
+                .filter(|c| c.compressed_points)
+                .map(|c| c.name)
+                .collect::<Vec<_>>(),
+            vec!["bn128"]
+        );
+    }
+}
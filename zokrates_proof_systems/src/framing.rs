@@ -0,0 +1,175 @@
+//! Length-prefixed binary encoding of curve points
+//!
+//! An element is written as a big-endian `u16` holding the length of its payload, followed by the payload: the
+//! number of coordinates of the element as a single byte, then the coordinates, all of the same size.
+//! This lets a reader check that it received a complete element before parsing it.
+
+use crate::{Fq, G1Affine, G2Affine, G2AffineFq, G2AffineFq2};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Read, Write};
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_framed<W: Write>(writer: &mut W, coordinates: &[&Fq]) -> io::Result<()> {
+    let coordinates = coordinates
+        .iter()
+        .map(|c| hex::decode(c.trim_start_matches("0x")).map_err(|e| invalid_data(e.to_string())))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // coordinates must have the same size for the reader to split them, so left-pad the shorter ones with zeros
+    let width = coordinates.iter().map(|c| c.len()).max().unwrap_or(0);
+
+    let payload: Vec<u8> = std::iter::once(coordinates.len() as u8)
+        .chain(
+            coordinates
+                .into_iter()
+                .flat_map(|c| std::iter::repeat(0).take(width - c.len()).chain(c)),
+        )
+        .collect();
+
+    let len = u16::try_from(payload.len())
+        .map_err(|_| invalid_data(format!("element of {} bytes is too large", payload.len())))?;
+
+    writer.write_u16::<BigEndian>(len)?;
+    writer.write_all(&payload)
+}
+
+fn read_framed<R: Read>(reader: &mut R, name: &str) -> io::Result<Vec<Fq>> {
+    let len = reader.read_u16::<BigEndian>().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("truncated {} element: missing length header", name),
+        )
+    })? as usize;
+
+    let mut payload = Vec::with_capacity(len);
+    reader.take(len as u64).read_to_end(&mut payload)?;
+
+    if payload.len() < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "truncated {} element: expected {} bytes, got {}",
+                name,
+                len,
+                payload.len()
+            ),
+        ));
+    }
+
+    let (count, coordinates) = payload
+        .split_first()
+        .ok_or_else(|| invalid_data(format!("empty {} element", name)))?;
+    let count = *count as usize;
+
+    if count == 0 || coordinates.len() % count != 0 {
+        return Err(invalid_data(format!(
+            "invalid {} element: {} bytes cannot hold {} coordinates of the same size",
+            name,
+            coordinates.len(),
+            count
+        )));
+    }
+
+    Ok(coordinates
+        .chunks(coordinates.len() / count)
+        .map(|c| format!("0x{}", hex::encode(c)))
+        .collect())
+}
+
+pub fn write_g1_framed<W: Write>(writer: &mut W, g1: &G1Affine) -> io::Result<()> {
+    write_framed(writer, &[&g1.0, &g1.1])
+}
+
+pub fn read_g1_framed<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+    match &read_framed(reader, "G1")?[..] {
+        [x, y] => Ok(G1Affine(x.clone(), y.clone())),
+        c => Err(invalid_data(format!(
+            "invalid G1 element: expected 2 coordinates, got {}",
+            c.len()
+        ))),
+    }
+}
+
+pub fn write_g2_framed<W: Write>(writer: &mut W, g2: &G2Affine) -> io::Result<()> {
+    match g2 {
+        G2Affine::Fq2(G2AffineFq2(x, y)) => write_framed(writer, &[&x.0, &x.1, &y.0, &y.1]),
+        G2Affine::Fq(G2AffineFq(x, y)) => write_framed(writer, &[x, y]),
+    }
+}
+
+pub fn read_g2_framed<R: Read>(reader: &mut R) -> io::Result<G2Affine> {
+    match &read_framed(reader, "G2")?[..] {
+        [x0, x1, y0, y1] => Ok(G2Affine::Fq2(G2AffineFq2(
+            (x0.clone(), x1.clone()),
+            (y0.clone(), y1.clone()),
+        ))),
+        [x, y] => Ok(G2Affine::Fq(G2AffineFq(x.clone(), y.clone()))),
+        c => Err(invalid_data(format!(
+            "invalid G2 element: expected 2 or 4 coordinates, got {}",
+            c.len()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fq(byte: u8) -> Fq {
+        format!("0x{}", hex::encode([byte; 32]))
+    }
+
+    #[test]
+    fn round_trip() {
+        let g1 = G1Affine(fq(1), fq(2));
+        let g2 = G2Affine::Fq2(G2AffineFq2((fq(3), fq(4)), (fq(5), fq(6))));
+
+        let mut buffer = vec![];
+        write_g1_framed(&mut buffer, &g1).unwrap();
+        write_g2_framed(&mut buffer, &g2).unwrap();
+
+        // 2 bytes of header, 1 byte for the coordinate count, 32 bytes per coordinate
+        assert_eq!(buffer.len(), (2 + 1 + 2 * 32) + (2 + 1 + 4 * 32));
+
+        let mut reader = &buffer[..];
+        let read_g1 = read_g1_framed(&mut reader).unwrap();
+        let read_g2 = read_g2_framed(&mut reader).unwrap();
+
+        assert_eq!((read_g1.0, read_g1.1), (g1.0, g1.1));
+        assert_eq!(read_g2.to_string(), g2.to_string());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn unequal_coordinates() {
+        let g1 = G1Affine("0x01".to_string(), fq(2));
+
+        let mut buffer = vec![];
+        write_g1_framed(&mut buffer, &g1).unwrap();
+
+        assert_eq!(buffer.len(), 2 + 1 + 2 * 32);
+
+        let read_g1 = read_g1_framed(&mut &buffer[..]).unwrap();
+
+        assert_eq!(read_g1.0, format!("0x{}01", "00".repeat(31)));
+        assert_eq!(read_g1.1, g1.1);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut buffer = vec![];
+        write_g1_framed(&mut buffer, &G1Affine(fq(1), fq(2))).unwrap();
+        buffer.truncate(40);
+
+        let error = read_g1_framed(&mut &buffer[..]).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            error.to_string(),
+            "truncated G1 element: expected 65 bytes, got 38"
+        );
+    }
+}
@@ -1,10 +1,23 @@
 pub mod rng;
 pub mod to_token;
 
+mod compression;
+mod curves;
+mod framing;
+mod public_inputs;
 mod scheme;
 mod solidity;
 mod tagged;
 
+pub use self::compression::{
+    read_g1_compressed, read_g1_compressed_vec, read_g2_compressed, read_g2_compressed_vec,
+    write_g1_compressed, write_g2_compressed,
+};
+pub use self::curves::{supported_curves, CurveInfo};
+pub use self::framing::{read_g1_framed, read_g2_framed, write_g1_framed, write_g2_framed};
+pub use self::public_inputs::{
+    check_public_input_arity, public_inputs_hash, PublicInputArityError,
+};
 pub use self::scheme::*;
 pub use self::solidity::*;
 pub use tagged::{TaggedKeypair, TaggedProof, TaggedVerificationKey};
@@ -13,6 +26,7 @@ use zokrates_ast::ir;
 
 use rand_0_8::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::io::{Read, Write};
 
 use zokrates_field::Field;
@@ -95,15 +109,55 @@ impl ToString for G2AffineFq2 {
     }
 }
 
+/// An error returned by a backend when given malformed inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    /// The proving key could not be deserialized
+    ProvingKey(String),
+    /// A point or a field element of a verification key or a proof could not be decoded
+    Encoding(String),
+    /// The backend failed to generate or verify a proof
+    Backend(String),
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BackendError::ProvingKey(e) => write!(f, "Invalid proving key: {}", e),
+            BackendError::Encoding(e) => write!(f, "Invalid encoding: {}", e),
+            BackendError::Backend(e) => write!(f, "Backend error: {}", e),
+        }
+    }
+}
+
 pub trait Backend<T: Field, S: Scheme<T>> {
     fn generate_proof<'a, I: IntoIterator<Item = ir::Statement<'a, T>>, R: RngCore + CryptoRng>(
         program: ir::ProgIterator<'a, T, I>,
         witness: ir::Witness<T>,
         proving_key: Vec<u8>,
         rng: &mut R,
-    ) -> Proof<T, S>;
+    ) -> Proof<T, S> {
+        Self::try_generate_proof(program, witness, proving_key, rng).unwrap()
+    }
+
+    fn verify(vk: S::VerificationKey, proof: Proof<T, S>) -> bool {
+        Self::try_verify(vk, proof).unwrap()
+    }
+
+    /// Same as `generate_proof`, returning an error if the proving key is malformed or the backend fails
+    fn try_generate_proof<
+        'a,
+        I: IntoIterator<Item = ir::Statement<'a, T>>,
+        R: RngCore + CryptoRng,
+    >(
+        program: ir::ProgIterator<'a, T, I>,
+        witness: ir::Witness<T>,
+        proving_key: Vec<u8>,
+        rng: &mut R,
+    ) -> Result<Proof<T, S>, BackendError>;
 
-    fn verify(vk: S::VerificationKey, proof: Proof<T, S>) -> bool;
+    /// Same as `verify`, returning an error if the verification key or the proof is malformed
+    fn try_verify(vk: S::VerificationKey, proof: Proof<T, S>) -> Result<bool, BackendError>;
 }
 pub trait NonUniversalBackend<T: Field, S: NonUniversalScheme<T>>: Backend<T, S> {
     fn setup<'a, I: IntoIterator<Item = ir::Statement<'a, T>>, R: RngCore + CryptoRng>(
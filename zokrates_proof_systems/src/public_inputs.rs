@@ -0,0 +1,130 @@
+use crate::Scheme;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use zokrates_field::Field;
+
+/// The number of public inputs does not match the one expected by the verification key
+#[derive(Debug, PartialEq, Eq)]
+pub struct PublicInputArityError {
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for PublicInputArityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Expected {} public inputs, found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+/// Check that `public_inputs` has as many elements as `vk` expects, before doing any verification work
+pub fn check_public_input_arity<T: Field, S: Scheme<T>>(
+    vk: &S::VerificationKey,
+    public_inputs: &[T],
+) -> Result<(), PublicInputArityError> {
+    let expected = S::public_input_count(vk);
+
+    match public_inputs.len() {
+        found if found == expected => Ok(()),
+        found => Err(PublicInputArityError { expected, found }),
+    }
+}
+
+/// Hash public inputs, in the order given by `Prog::public_inputs_values`, with SHA-256
+///
+/// Each input is encoded as a big-endian integer on as many bytes as the largest element of `T`,
+/// which is 32 bytes for fields of at most 256 bits.
+pub fn public_inputs_hash<T: Field>(public_inputs: &[T]) -> [u8; 32] {
+    let width = T::max_value().to_biguint().to_bytes_be().len();
+
+    let mut h = Sha256::default();
+
+    for input in public_inputs {
+        let bytes = input.to_biguint().to_bytes_be();
+
+        let mut encoding = vec![0u8; width];
+        encoding[width - bytes.len()..].copy_from_slice(&bytes);
+        h.input(&encoding);
+    }
+
+    let mut res = [0u8; 32];
+    res.copy_from_slice(&h.result());
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{groth16, G1Affine, G2Affine, G2AffineFq2, G16};
+    use zokrates_field::{Bn128Field, Bw6_761Field};
+
+    #[test]
+    fn arity() {
+        let g1 = || G1Affine("0x0".into(), "0x0".into());
+        let g2 = || {
+            G2Affine::Fq2(G2AffineFq2(
+                ("0x0".into(), "0x0".into()),
+                ("0x0".into(), "0x0".into()),
+            ))
+        };
+
+        // one constant term and two public inputs
+        let vk = groth16::VerificationKey {
+            alpha: g1(),
+            beta: g2(),
+            gamma: g2(),
+            delta: g2(),
+            gamma_abc: vec![g1(), g1(), g1()],
+        };
+
+        let inputs = [Bn128Field::from(1), Bn128Field::from(2)];
+
+        assert_eq!(check_public_input_arity::<_, G16>(&vk, &inputs), Ok(()));
+        assert_eq!(
+            check_public_input_arity::<_, G16>(&vk, &inputs[..1]),
+            Err(PublicInputArityError {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            PublicInputArityError {
+                expected: 2,
+                found: 1
+            }
+            .to_string(),
+            "Expected 2 public inputs, found 1"
+        );
+    }
+
+    #[test]
+    fn stable_and_order_dependent() {
+        let inputs = [Bn128Field::from(1), Bn128Field::from(2)];
+
+        // sha256 of the 32-byte encodings of 1 and 2
+        assert_eq!(
+            hex::encode(public_inputs_hash(&inputs)),
+            "d6ba9329f8932c12192b37849f772104d20048f76434a3290512d9d814e4116f"
+        );
+
+        let reversed = [Bn128Field::from(2), Bn128Field::from(1)];
+        assert_ne!(public_inputs_hash(&inputs), public_inputs_hash(&reversed));
+    }
+
+    #[test]
+    fn wide_field() {
+        // elements of the bw6_761 scalar field take 48 bytes
+        let inputs = [Bw6_761Field::max_value(), Bw6_761Field::from(1)];
+
+        let mut expected = Sha256::default();
+        expected.input(&Bw6_761Field::max_value().to_biguint().to_bytes_be());
+        let mut one = [0u8; 48];
+        one[47] = 1;
+        expected.input(&one);
+
+        assert_eq!(public_inputs_hash(&inputs)[..], expected.result()[..]);
+    }
+}
@@ -33,6 +33,11 @@ impl<T: Field> Scheme<T> for GM17 {
 
     type VerificationKey = VerificationKey<G1Affine, G2Affine>;
     type ProofPoints = ProofPoints<G1Affine, G2Affine>;
+
+    fn public_input_count(vk: &Self::VerificationKey) -> usize {
+        // the first element is the constant term
+        vk.query.len().saturating_sub(1)
+    }
 }
 
 impl<T: SolidityCompatibleField> SolidityCompatibleScheme<T> for GM17 {
@@ -197,3 +202,31 @@ contract Verifier {
     }
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::tests::roundtrip_vk;
+    use crate::G2AffineFq2;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn vk_roundtrip() {
+        let g1 = |i: usize| G1Affine(format!("0x{:064x}", i), format!("0x{:064x}", i + 1));
+        let g2 = |i: usize| {
+            G2Affine::Fq2(G2AffineFq2(
+                (format!("0x{:064x}", i), format!("0x{:064x}", i + 1)),
+                (format!("0x{:064x}", i + 2), format!("0x{:064x}", i + 3)),
+            ))
+        };
+
+        roundtrip_vk::<Bn128Field, GM17>(VerificationKey {
+            h: g2(0),
+            g_alpha: g1(4),
+            h_beta: g2(6),
+            g_gamma: g1(10),
+            h_gamma: g2(12),
+            query: vec![g1(16), g1(18)],
+        });
+    }
+}
@@ -29,6 +29,11 @@ impl<T: Field> Scheme<T> for G16 {
 
     type VerificationKey = VerificationKey<G1Affine, G2Affine>;
     type ProofPoints = ProofPoints<G1Affine, G2Affine>;
+
+    fn public_input_count(vk: &Self::VerificationKey) -> usize {
+        // the first element is the constant term
+        vk.gamma_abc.len().saturating_sub(1)
+    }
 }
 
 impl<T: Field> NonUniversalScheme<T> for G16 {}
@@ -184,3 +189,30 @@ contract Verifier {
     }
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::tests::roundtrip_vk;
+    use crate::G2AffineFq2;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn vk_roundtrip() {
+        let g1 = |i: usize| G1Affine(format!("0x{:064x}", i), format!("0x{:064x}", i + 1));
+        let g2 = |i: usize| {
+            G2Affine::Fq2(G2AffineFq2(
+                (format!("0x{:064x}", i), format!("0x{:064x}", i + 1)),
+                (format!("0x{:064x}", i + 2), format!("0x{:064x}", i + 3)),
+            ))
+        };
+
+        roundtrip_vk::<Bn128Field, G16>(VerificationKey {
+            alpha: g1(0),
+            beta: g2(2),
+            gamma: g2(6),
+            delta: g2(10),
+            gamma_abc: vec![g1(14), g1(16)],
+        });
+    }
+}
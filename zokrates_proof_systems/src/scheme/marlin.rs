@@ -83,6 +83,10 @@ impl<T: Field> Scheme<T> for Marlin {
 
     type VerificationKey = VerificationKey<Fr, G1Affine, G2Affine>;
     type ProofPoints = ProofPoints<Fr, G1Affine>;
+
+    fn public_input_count(vk: &Self::VerificationKey) -> usize {
+        vk.num_public_inputs
+    }
 }
 
 impl<T: Field> UniversalScheme<T> for Marlin {}
@@ -15,6 +15,9 @@ pub trait Scheme<T: Field>: Serialize {
 
     type VerificationKey: Serialize + DeserializeOwned;
     type ProofPoints: Serialize + DeserializeOwned;
+
+    /// The number of public inputs a proof must provide to be verified against `vk`
+    fn public_input_count(vk: &Self::VerificationKey) -> usize;
 }
 
 pub trait NonUniversalScheme<T: Field>: Scheme<T> {}
@@ -22,3 +25,15 @@ pub trait NonUniversalScheme<T: Field>: Scheme<T> {}
 pub trait UniversalScheme<T: Field>: Scheme<T> {}
 
 pub trait MpcScheme<T: Field>: Scheme<T> {}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Writes `vk` to JSON, reads it back and checks that writing it again yields the same bytes
+    pub fn roundtrip_vk<T: Field, S: Scheme<T>>(vk: S::VerificationKey) {
+        let json = serde_json::to_vec(&vk).unwrap();
+        let read: S::VerificationKey = serde_json::from_slice(&json).unwrap();
+        assert_eq!(serde_json::to_vec(&read).unwrap(), json);
+    }
+}